@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+
+use crate::grid::{Direction, GridPos};
+
+/// Resultado de uma busca de caminho, guardado para a sobreposição de
+/// depuração.
+#[derive(Clone, Debug, Default)]
+pub struct Search {
+    /// Células visitadas, na ordem em que a busca chegou a elas.
+    pub visited: Vec<GridPos>,
+    /// Caminho mais curto até o alvo, sem a célula de partida. Vazio se o
+    /// alvo não foi alcançado.
+    pub path: Vec<GridPos>,
+}
+
+impl Search {
+    /// Direção do primeiro passo do caminho, a partir de `start`.
+    pub fn first_direction(&self, start: GridPos) -> Option<Direction> {
+        let first = *self.path.first()?;
+        Direction::ALL.into_iter().find(|&dir| start.neighbor(dir) == first)
+    }
+}
+
+/// Busca em largura de `start` até `target` numa grade `width` x `height`.
+/// `blocked` indica as células intransponíveis; o alvo é sempre aceito.
+/// Um `start` fora da grade dá um resultado vazio.
+pub fn search(
+    start: GridPos,
+    target: GridPos,
+    width: i32,
+    height: i32,
+    blocked: impl Fn(GridPos) -> bool,
+) -> Search {
+    let mut result = Search::default();
+    let inside = |p: GridPos| p.x >= 0 && p.y >= 0 && p.x < width && p.y < height;
+    if !inside(start) {
+        return result;
+    }
+    let index = |p: GridPos| (p.y * width + p.x) as usize;
+    let mut visited = vec![false; (width * height) as usize];
+    // Célula de onde a busca chegou a cada célula, para refazer o caminho.
+    let mut came_from: Vec<Option<GridPos>> = vec![None; (width * height) as usize];
+    let mut queue = VecDeque::new();
+
+    visited[index(start)] = true;
+    queue.push_back(start);
+
+    while let Some(pos) = queue.pop_front() {
+        result.visited.push(pos);
+        if pos == target {
+            let mut cell = pos;
+            while let Some(prev) = came_from[index(cell)] {
+                result.path.push(cell);
+                cell = prev;
+            }
+            result.path.reverse();
+            return result;
+        }
+        for dir in Direction::ALL {
+            let next = pos.neighbor(dir);
+            if !inside(next) || visited[index(next)] {
+                continue;
+            }
+            if next != target && blocked(next) {
+                continue;
+            }
+            visited[index(next)] = true;
+            came_from[index(next)] = Some(pos);
+            queue.push_back(next);
+        }
+    }
+    result
+}
+
+/// Qualquer direção que não leve a uma célula bloqueada, preferindo `current`.
+/// Usada quando não existe caminho até o alvo.
+pub fn safe_direction(
+    start: GridPos,
+    current: Direction,
+    blocked: impl Fn(GridPos) -> bool,
+) -> Option<Direction> {
+    std::iter::once(current)
+        .chain(Direction::ALL)
+        .find(|&dir| !blocked(start.neighbor(dir)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_from_outside_the_grid_is_empty() {
+        for start in [GridPos::new(-1, 2), GridPos::new(5, 2), GridPos::new(2, 4)] {
+            let result = search(start, GridPos::new(1, 1), 5, 4, |_| false);
+            assert!(result.visited.is_empty() && result.path.is_empty(), "{start:?}");
+        }
+        assert!(search(GridPos::new(0, 0), GridPos::new(0, 0), 0, 0, |_| false).visited.is_empty());
+    }
+
+    #[test]
+    fn search_finds_the_shortest_path() {
+        let result = search(GridPos::new(0, 0), GridPos::new(2, 0), 3, 3, |p| p == GridPos::new(1, 0));
+        assert_eq!(result.path.len(), 4);
+        assert_eq!(result.path.last(), Some(&GridPos::new(2, 0)));
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::Config;
+use crate::leaderboard::Leaderboard;
+use crate::{BLOCK_SIZE, DeathCause, GameMode, GameState};
+
+/// Variável de ambiente que troca a janela pelo teste do piloto automático.
+/// O valor é o número de partidas; vazio ou inválido usa `DEFAULT_RUNS`.
+const BENCHMARK_ENV: &str = "RUSTED_SNAKE_BENCHMARK";
+const DEFAULT_RUNS: u32 = 1000;
+// Passos máximos de uma partida, para que um piloto preso num ciclo não
+// trave o teste.
+const MAX_TICKS: u32 = 100_000;
+
+/// Como terminou uma partida do teste.
+struct RunResult {
+    score: u32,
+    ticks: u32,
+    /// `None` quando a partida chegou a `MAX_TICKS` sem morrer.
+    cause: Option<DeathCause>,
+}
+
+/// Quantas partidas o teste deve jogar, se a variável de ambiente pede um.
+pub fn requested_runs() -> Option<u32> {
+    std::env::var(BENCHMARK_ENV)
+        .ok()
+        .map(|value| value.trim().parse().unwrap_or(DEFAULT_RUNS))
+}
+
+/// Joga `runs` partidas com o piloto automático, sem janela e sem esperar
+/// pelos quadros, e imprime as estatísticas no terminal. A arena tem o
+/// tamanho da janela configurada, e as regras são as da configuração.
+/// As sementes saem de uma sequência fixa, então duas execuções com a mesma
+/// configuração jogam as mesmas partidas.
+pub fn run(config: Config, runs: u32) {
+    let mode = config.window_mode();
+    let (width, height) = config.grid_size(mode.width, mode.height, BLOCK_SIZE);
+    let mut seeds = StdRng::seed_from_u64(0);
+    let mut state = GameState::from_parts(config, Leaderboard::default(), width, height, 0);
+    let started = Instant::now();
+    let results: Vec<RunResult> = (0..runs).map(|_| play(&mut state, seeds.random())).collect();
+    report(&results, width, height, started.elapsed());
+}
+
+// Só o núcleo da partida roda: os passos, sem os cronômetros de `update`.
+fn play(state: &mut GameState, seed: u64) -> RunResult {
+    state.start_run(seed, None);
+    state.death_cause = None;
+    let mut ticks = 0;
+    while matches!(state.mode, GameMode::Playing) && ticks < MAX_TICKS {
+        if let Some(direction) = state.autopilot_direction() {
+            state.snake.direction = direction;
+        }
+        state.step();
+        // Sem janela ninguém consome os eventos; o placar também fica de fora.
+        state.events.clear();
+        ticks += 1;
+    }
+    RunResult {
+        score: state.score,
+        ticks,
+        cause: state.death_cause,
+    }
+}
+
+fn report(results: &[RunResult], width: i32, height: i32, elapsed: Duration) {
+    println!("Partidas: {} numa arena de {}x{}, em {:.1}s", results.len(), width, height, elapsed.as_secs_f32());
+    if results.is_empty() {
+        return;
+    }
+    let count = results.len() as f32;
+    let mut scores: Vec<u32> = results.iter().map(|r| r.score).collect();
+    scores.sort_unstable();
+    let mid = scores.len() / 2;
+    let median = if scores.len().is_multiple_of(2) {
+        (scores[mid - 1] + scores[mid]) as f32 / 2.0
+    } else {
+        scores[mid] as f32
+    };
+    let mean = scores.iter().sum::<u32>() as f32 / count;
+    println!("Pontuação: média {:.1}, mediana {:.1}, máxima {}", mean, median, scores[scores.len() - 1]);
+    let ticks = results.iter().map(|r| r.ticks as f32).sum::<f32>() / count;
+    println!("Passos por partida: média {:.1}", ticks);
+
+    for cause in DeathCause::ALL {
+        let deaths = results.iter().filter(|r| r.cause == Some(cause)).count();
+        if deaths > 0 {
+            println!("  {}: {}", cause.message(), deaths);
+        }
+    }
+    let survived = results.iter().filter(|r| r.cause.is_none()).count();
+    if survived > 0 {
+        println!("  Vivas após {} passos: {}", MAX_TICKS, survived);
+    }
+}
@@ -0,0 +1,118 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::Snake;
+use crate::grid::{Direction, GridPos};
+
+/// Pontos que vencem uma corrida na tela dividida.
+pub const TARGET_SCORE: u32 = 10;
+// Segundos entre dois passos; as duas arenas andam juntas.
+pub const STEP_INTERVAL: f32 = 0.12;
+
+const START_HEAD: GridPos = GridPos::new(3, 2);
+
+/// Uma arena independente da tela dividida: cobra, comida e pontuação
+/// próprias, com regras simples (paredes e cauda matam).
+pub struct Board {
+    pub snake: Snake,
+    pub food: GridPos,
+    pub score: u32,
+    pub alive: bool,
+    pub width: i32,
+    pub height: i32,
+    next_direction: Option<Direction>,
+    rng: StdRng,
+}
+
+/// Como terminou uma corrida.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RaceResult {
+    /// Índice da arena vencedora.
+    Winner(usize),
+    Draw,
+}
+
+impl Board {
+    /// Com a mesma semente, duas arenas sorteiam a comida nas mesmas células
+    /// enquanto elas estiverem livres nas duas.
+    pub fn new(width: i32, height: i32, seed: u64) -> Board {
+        let mut board = Board {
+            snake: Snake::new(START_HEAD, Direction::Right),
+            food: GridPos::default(),
+            score: 0,
+            alive: true,
+            width,
+            height,
+            next_direction: None,
+            rng: StdRng::seed_from_u64(seed),
+        };
+        board.place_food();
+        board
+    }
+
+    pub fn is_wall(&self, pos: GridPos) -> bool {
+        pos.x <= 0 || pos.y <= 0 || pos.x >= self.width - 1 || pos.y >= self.height - 1
+    }
+
+    /// Guarda a virada para o próximo passo; voltar para trás é ignorado.
+    pub fn turn(&mut self, direction: Direction) {
+        if direction != self.snake.direction.opposite() {
+            self.next_direction = Some(direction);
+        }
+    }
+
+    pub fn step(&mut self) {
+        if !self.alive {
+            return;
+        }
+        if let Some(direction) = self.next_direction.take() {
+            self.snake.direction = direction;
+        }
+        self.snake.move_forward();
+        let head = self.snake.head();
+        if self.is_wall(head) || self.snake.is_overlapping_tail() {
+            self.alive = false;
+            return;
+        }
+        if head == self.food {
+            if let Some(tail) = self.snake.tail.take() {
+                self.snake.body.push_back(tail);
+            }
+            self.score += 1;
+            self.place_food();
+        }
+    }
+
+    // Sorteia entre todas as células internas e repete se cair na cobra, para
+    // que a sequência dependa só da semente, e não do formato da cobra.
+    fn place_food(&mut self) {
+        let cells = (self.width - 2) * (self.height - 2);
+        if self.snake.body.len() as i32 >= cells {
+            return;
+        }
+        loop {
+            let pos = GridPos::new(self.rng.random_range(1..self.width - 1), self.rng.random_range(1..self.height - 1));
+            if !self.snake.occupies(pos) {
+                self.food = pos;
+                return;
+            }
+        }
+    }
+}
+
+/// O resultado da corrida, se ela já acabou: chegar a `TARGET_SCORE` vence,
+/// e morrer perde.
+pub fn race_result(boards: &[Board; 2]) -> Option<RaceResult> {
+    let done = |b: &Board| !b.alive || b.score >= TARGET_SCORE;
+    let won = |b: &Board| b.alive && b.score >= TARGET_SCORE;
+    match (done(&boards[0]), done(&boards[1])) {
+        (false, false) => None,
+        (true, false) => Some(if won(&boards[0]) { RaceResult::Winner(0) } else { RaceResult::Winner(1) }),
+        (false, true) => Some(if won(&boards[1]) { RaceResult::Winner(1) } else { RaceResult::Winner(0) }),
+        (true, true) => Some(match (won(&boards[0]), won(&boards[1])) {
+            (true, false) => RaceResult::Winner(0),
+            (false, true) => RaceResult::Winner(1),
+            _ => RaceResult::Draw,
+        }),
+    }
+}
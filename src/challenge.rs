@@ -0,0 +1,337 @@
+use crate::config::{Config, LethalMealPolicy, ReversePolicy};
+
+// Muda quando o formato do código mudar, para recusar códigos antigos.
+const VERSION: u8 = 3;
+// Alfabeto do base64 para URLs: o código pode ir num link sem escapes.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+// Limites da grade aceitos num código: a cobra inicial precisa caber, e uma
+// arena maior que isso nenhuma janela produz.
+const MIN_GRID_SIDE: u16 = 8;
+const MAX_GRID_SIDE: u16 = 1024;
+
+/// Semente, tamanho da grade e regras de uma partida, o suficiente para outra
+/// pessoa jogar a mesma disposição de comida e inimigos. O roteiro de comida
+/// é de quem o escreveu e não vai no código.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Challenge {
+    pub seed: u64,
+    pub grid_width: u16,
+    pub grid_height: u16,
+    pub wall_bounce: bool,
+    pub wrap_edges: bool,
+    pub moving_food: bool,
+    pub shrinking_arena: bool,
+    pub passable_border: bool,
+    pub speed_pads: bool,
+    pub food_enabled: bool,
+    pub special_food: bool,
+    pub instant_turns: bool,
+    pub reverse_policy: ReversePolicy,
+    pub lethal_meal: LethalMealPolicy,
+    pub enemy_count: u8,
+    pub frames_per_tick: u8,
+    pub starting_score: u8,
+    pub wall_lives: u8,
+    /// Zero quando a cobra cresce sem limite.
+    pub max_length: u16,
+    pub maze_density: f32,
+    pub food_center_bias: f32,
+    pub curse_chance: f32,
+    pub curse_duration: f32,
+    pub trail_wall_delay: f32,
+    pub starting_speed_offset: f32,
+    pub start_grace: f32,
+    pub food_magnet_interval: f32,
+    pub chain_window: f32,
+}
+
+impl Challenge {
+    /// O desafio da partida jogada com esta semente, nesta grade e com estas
+    /// configurações.
+    pub fn from_config(seed: u64, (width, height): (i32, i32), config: &Config) -> Challenge {
+        let byte = |value: u32| value.min(u8::MAX as u32) as u8;
+        Challenge {
+            seed,
+            grid_width: width.clamp(0, u16::MAX as i32) as u16,
+            grid_height: height.clamp(0, u16::MAX as i32) as u16,
+            wall_bounce: config.wall_bounce,
+            wrap_edges: config.wrap_edges,
+            moving_food: config.moving_food,
+            shrinking_arena: config.shrinking_arena,
+            passable_border: config.passable_border,
+            speed_pads: config.speed_pads,
+            food_enabled: config.food_enabled,
+            special_food: config.special_food,
+            instant_turns: config.instant_turns,
+            reverse_policy: config.reverse_policy,
+            lethal_meal: config.lethal_meal,
+            enemy_count: byte(config.enemy_count),
+            frames_per_tick: byte(config.frames_per_tick),
+            starting_score: byte(config.starting_score),
+            wall_lives: byte(config.wall_lives),
+            max_length: config.max_length.map_or(0, |len| len.min(u16::MAX as usize) as u16),
+            maze_density: config.maze_density,
+            food_center_bias: config.food_center_bias,
+            curse_chance: config.curse_chance,
+            curse_duration: config.curse_duration,
+            trail_wall_delay: config.trail_wall_delay,
+            starting_speed_offset: config.starting_speed_offset,
+            start_grace: config.start_grace,
+            food_magnet_interval: config.food_magnet_interval,
+            chain_window: config.chain_window,
+        }
+    }
+
+    /// Troca as regras e o tamanho da grade da configuração pelos do desafio.
+    pub fn apply(&self, config: &mut Config) {
+        config.fixed_grid = Some((self.grid_width as i32, self.grid_height as i32));
+        config.wall_bounce = self.wall_bounce;
+        config.wrap_edges = self.wrap_edges;
+        config.moving_food = self.moving_food;
+        config.shrinking_arena = self.shrinking_arena;
+        config.passable_border = self.passable_border;
+        config.speed_pads = self.speed_pads;
+        config.food_enabled = self.food_enabled;
+        config.special_food = self.special_food;
+        config.instant_turns = self.instant_turns;
+        config.reverse_policy = self.reverse_policy;
+        config.lethal_meal = self.lethal_meal;
+        config.enemy_count = self.enemy_count as u32;
+        config.frames_per_tick = self.frames_per_tick as u32;
+        config.starting_score = self.starting_score as u32;
+        config.wall_lives = self.wall_lives as u32;
+        config.max_length = (self.max_length > 0).then_some(self.max_length as usize);
+        config.maze_density = self.maze_density;
+        config.food_center_bias = self.food_center_bias;
+        config.curse_chance = self.curse_chance;
+        config.curse_duration = self.curse_duration;
+        config.trail_wall_delay = self.trail_wall_delay;
+        config.starting_speed_offset = self.starting_speed_offset;
+        config.start_grace = self.start_grace;
+        config.food_magnet_interval = self.food_magnet_interval;
+        config.chain_window = self.chain_window;
+        config.food_script.clear();
+    }
+
+    fn flags(&self) -> u16 {
+        [
+            self.wall_bounce,
+            self.wrap_edges,
+            self.moving_food,
+            self.shrinking_arena,
+            self.passable_border,
+            self.speed_pads,
+            self.food_enabled,
+            self.instant_turns,
+            self.reverse_policy == ReversePolicy::Lethal,
+            self.lethal_meal == LethalMealPolicy::DieWithoutScore,
+            self.special_food,
+        ]
+        .iter()
+        .enumerate()
+        .fold(0, |flags, (bit, &on)| flags | (on as u16) << bit)
+    }
+
+    fn numbers(&self) -> [f32; 9] {
+        [
+            self.maze_density,
+            self.food_center_bias,
+            self.curse_chance,
+            self.curse_duration,
+            self.trail_wall_delay,
+            self.starting_speed_offset,
+            self.start_grace,
+            self.food_magnet_interval,
+            self.chain_window,
+        ]
+    }
+
+    /// Código curto para compartilhar: versão, semente, regras, grade e os
+    /// ajustes numéricos em base64.
+    pub fn encode(&self) -> String {
+        let mut bytes = vec![VERSION];
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.flags().to_le_bytes());
+        bytes.extend_from_slice(&self.grid_width.to_le_bytes());
+        bytes.extend_from_slice(&self.grid_height.to_le_bytes());
+        bytes.extend_from_slice(&[self.enemy_count, self.frames_per_tick, self.starting_score, self.wall_lives]);
+        bytes.extend_from_slice(&self.max_length.to_le_bytes());
+        for value in self.numbers() {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        encode_base64(&bytes)
+    }
+
+    /// Lê um código gerado por `encode`. Maiúsculas e minúsculas importam.
+    /// Códigos com valores fora das faixas da tela de configurações, ou com
+    /// uma grade que nenhuma janela produz, são recusados.
+    pub fn decode(code: &str) -> Option<Challenge> {
+        let bytes = decode_base64(code.trim())?;
+        let mut rest = bytes.as_slice();
+        let [version] = take(&mut rest)?;
+        if version != VERSION {
+            return None;
+        }
+        let seed = u64::from_le_bytes(take(&mut rest)?);
+        let flags = u16::from_le_bytes(take(&mut rest)?);
+        let grid_width = u16::from_le_bytes(take(&mut rest)?);
+        let grid_height = u16::from_le_bytes(take(&mut rest)?);
+        let [enemy_count, frames_per_tick, starting_score, wall_lives] = take(&mut rest)?;
+        let max_length = u16::from_le_bytes(take(&mut rest)?);
+        let mut numbers = [0.0; 9];
+        for number in &mut numbers {
+            *number = f32::from_le_bytes(take(&mut rest)?);
+        }
+        let [
+            maze_density,
+            food_center_bias,
+            curse_chance,
+            curse_duration,
+            trail_wall_delay,
+            starting_speed_offset,
+            start_grace,
+            food_magnet_interval,
+            chain_window,
+        ] = numbers;
+        let grid = MIN_GRID_SIDE..=MAX_GRID_SIDE;
+        if !rest.is_empty() || !grid.contains(&grid_width) || !grid.contains(&grid_height) {
+            return None;
+        }
+        let flag = |bit: u8| flags & (1 << bit) != 0;
+        let challenge = Challenge {
+            seed,
+            grid_width,
+            grid_height,
+            wall_bounce: flag(0),
+            wrap_edges: flag(1),
+            moving_food: flag(2),
+            shrinking_arena: flag(3),
+            passable_border: flag(4),
+            speed_pads: flag(5),
+            food_enabled: flag(6),
+            instant_turns: flag(7),
+            reverse_policy: if flag(8) { ReversePolicy::Lethal } else { ReversePolicy::Ignore },
+            lethal_meal: if flag(9) { LethalMealPolicy::DieWithoutScore } else { LethalMealPolicy::ScoreThenDie },
+            special_food: flag(10),
+            enemy_count,
+            frames_per_tick,
+            starting_score,
+            wall_lives,
+            max_length,
+            maze_density,
+            food_center_bias,
+            curse_chance,
+            curse_duration,
+            trail_wall_delay,
+            starting_speed_offset,
+            start_grace,
+            food_magnet_interval,
+            chain_window,
+        };
+        // As faixas são as mesmas que `Config::clamp_ranges` impõe ao arquivo:
+        // um valor que ela mudaria não saiu da tela de configurações. Um NaN
+        // também não passa, porque nunca é igual a si mesmo.
+        let mut config = Config::default();
+        challenge.apply(&mut config);
+        config.clamp_ranges();
+        let grid = (grid_width as i32, grid_height as i32);
+        (Challenge::from_config(seed, grid, &config) == challenge).then_some(challenge)
+    }
+}
+
+/// Tira os primeiros `N` bytes de `bytes`, se houver tantos.
+fn take<const N: usize>(bytes: &mut &[u8]) -> Option<[u8; N]> {
+    let (head, tail) = bytes.split_first_chunk::<N>()?;
+    *bytes = tail;
+    Some(*head)
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        // Um pedaço de k bytes vira k + 1 caracteres; o preenchimento é omitido.
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let values: Vec<u32> = text
+        .bytes()
+        .map(|c| ALPHABET.iter().position(|&a| a == c).map(|v| v as u32))
+        .collect::<Option<_>>()?;
+    let mut out = Vec::new();
+    for chunk in values.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &v)| n | v << (18 - 6 * i));
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{MAX_ENEMIES, MAX_MAZE_DENSITY};
+    use crate::grid::GridPos;
+
+    const GRID: (i32, i32) = (32, 24);
+
+    #[test]
+    fn code_round_trips_every_rule() {
+        let config = Config {
+            wall_bounce: true,
+            passable_border: true,
+            food_enabled: false,
+            special_food: true,
+            instant_turns: true,
+            reverse_policy: ReversePolicy::Lethal,
+            lethal_meal: LethalMealPolicy::DieWithoutScore,
+            enemy_count: 2,
+            frames_per_tick: 4,
+            starting_score: 5,
+            wall_lives: 3,
+            max_length: Some(40),
+            maze_density: 0.15,
+            food_center_bias: 1.5,
+            curse_chance: 0.3,
+            curse_duration: 4.0,
+            trail_wall_delay: 2.5,
+            starting_speed_offset: 0.05,
+            start_grace: 1.5,
+            food_magnet_interval: 2.0,
+            chain_window: 3.5,
+            ..Config::default()
+        };
+        let challenge = Challenge::from_config(0xdead_beef_1234, GRID, &config);
+        assert_eq!(Challenge::decode(&challenge.encode()), Some(challenge));
+
+        let mut applied = Config { food_script: vec![GridPos::new(4, 4)], ..Config::default() };
+        challenge.apply(&mut applied);
+        assert_eq!(Challenge::from_config(challenge.seed, GRID, &applied), challenge);
+        assert_eq!(applied.fixed_grid, Some(GRID));
+        assert!(applied.food_script.is_empty());
+    }
+
+    #[test]
+    fn decode_rejects_values_the_settings_do_not_allow() {
+        let base = Challenge::from_config(1, GRID, &Config::default());
+        let invalid = [
+            Challenge { enemy_count: MAX_ENEMIES as u8 + 1, ..base },
+            Challenge { maze_density: MAX_MAZE_DENSITY + 0.1, ..base },
+            Challenge { curse_chance: f32::NAN, ..base },
+            Challenge { max_length: 1, ..base },
+            Challenge { grid_width: 2, ..base },
+        ];
+        for challenge in invalid {
+            assert_eq!(Challenge::decode(&challenge.encode()), None, "{challenge:?}");
+        }
+    }
+}
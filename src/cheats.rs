@@ -0,0 +1,89 @@
+use ggez::input::keyboard::KeyCode;
+use std::collections::VecDeque;
+
+/// Efeitos que podem ser liberados digitando um código no menu.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Cheat {
+    /// Liga a sobreposição de depuração e as teclas de teste.
+    Debug,
+    /// As partidas já começam na velocidade máxima.
+    TopSpeed,
+}
+
+impl Cheat {
+    /// Texto mostrado no menu quando o código é aceito.
+    pub fn message(&self) -> &'static str {
+        match *self {
+            Cheat::Debug => "Depuração liberada",
+            Cheat::TopSpeed => "Velocidade máxima",
+        }
+    }
+}
+
+struct CheatCode {
+    keys: &'static [KeyCode],
+    cheat: Cheat,
+}
+
+// Para criar um código novo basta acrescentar uma entrada aqui.
+const CHEAT_CODES: &[CheatCode] = &[
+    CheatCode {
+        keys: &[
+            KeyCode::Up,
+            KeyCode::Up,
+            KeyCode::Down,
+            KeyCode::Down,
+            KeyCode::Left,
+            KeyCode::Right,
+            KeyCode::Left,
+            KeyCode::Right,
+            KeyCode::B,
+            KeyCode::A,
+        ],
+        cheat: Cheat::Debug,
+    },
+    CheatCode {
+        keys: &[KeyCode::F, KeyCode::A, KeyCode::S, KeyCode::T],
+        cheat: Cheat::TopSpeed,
+    },
+];
+
+/// Quantas teclas recentes precisam ser guardadas para reconhecer o maior código.
+pub fn max_code_length() -> usize {
+    CHEAT_CODES.iter().map(|c| c.keys.len()).max().unwrap_or(0)
+}
+
+/// O código que as últimas teclas digitadas completam, se houver.
+pub fn find(recent: &VecDeque<KeyCode>) -> Option<Cheat> {
+    CHEAT_CODES
+        .iter()
+        .find(|code| {
+            recent.len() >= code.keys.len() && recent.iter().skip(recent.len() - code.keys.len()).eq(code.keys.iter())
+        })
+        .map(|code| code.cheat)
+}
+
+/// Indica se as últimas teclas digitadas são o começo de algum código, ainda
+/// sem completá-lo.
+pub fn in_progress(recent: &VecDeque<KeyCode>) -> bool {
+    CHEAT_CODES.iter().any(|code| {
+        (1..code.keys.len()).any(|len| {
+            recent.len() >= len && recent.iter().skip(recent.len() - len).eq(code.keys[..len].iter())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_codes_are_in_progress() {
+        let keys = |keys: &[KeyCode]| keys.iter().copied().collect::<VecDeque<_>>();
+        assert!(in_progress(&keys(&[KeyCode::Up, KeyCode::Up, KeyCode::Down, KeyCode::Down, KeyCode::Left])));
+        assert!(in_progress(&keys(&[KeyCode::Left, KeyCode::F, KeyCode::A])));
+        assert!(!in_progress(&keys(&[KeyCode::Up, KeyCode::Left])));
+        assert!(!in_progress(&keys(&[KeyCode::D])));
+        assert!(!in_progress(&keys(&[])));
+    }
+}
@@ -0,0 +1,514 @@
+use ggez::conf::{FullscreenType, NumSamples, WindowMode};
+use ggez::filesystem::Filesystem;
+use ggez::{Context, GameResult};
+use serde::{Deserialize, Serialize};
+
+use crate::grid::GridPos;
+use crate::theme::NOKIA_THEME;
+
+// Arquivo salvo no diretório de configuração do usuário (gerenciado pela ggez).
+const CONFIG_PATH: &str = "/config.json";
+
+pub const MIN_CELL_SCALE: f32 = 0.5;
+pub const MAX_CELL_SCALE: f32 = 2.0;
+pub const MAX_ARENA_SCALE: u32 = 3;
+pub const MIN_ZOOM: f32 = 1.0;
+pub const MAX_ZOOM: f32 = 3.0;
+pub const MIN_UI_SCALE: f32 = 1.0;
+pub const MAX_UI_SCALE: f32 = 3.0;
+pub const MAX_ENEMIES: u32 = 3;
+// Acima disso os pesos do sorteio da comida estouram o `f32`.
+pub const MAX_FOOD_CENTER_BIAS: f32 = 3.0;
+// Tamanho com que a cobra começa; um limite menor não faria sentido.
+pub const MIN_MAX_LENGTH: usize = 3;
+pub const MAX_STARTING_SCORE: u32 = 20;
+pub const MAX_SPEED_OFFSET: f32 = 0.10;
+pub const MIN_PULSE_BPM: u32 = 60;
+pub const MAX_PULSE_BPM: u32 = 200;
+pub const MAX_AUTO_RETURN_DELAY: u32 = 60;
+pub const MAX_PROJECTION_LENGTH: u32 = 10;
+pub const MAX_WALL_LIVES: u32 = 5;
+pub const MAX_START_GRACE: f32 = 3.0;
+pub const MAX_FRAMES_PER_TICK: u32 = 20;
+pub const MAX_MAGNET_INTERVAL: f32 = 3.0;
+pub const MAX_DAY_CYCLE_MINUTES: u32 = 30;
+pub const MAX_MAZE_DENSITY: f32 = 0.3;
+pub const MAX_TRAIL_WALL_DELAY: f32 = 30.0;
+pub const MAX_CHAIN_WINDOW: f32 = 10.0;
+pub const MIN_PARTICLES: usize = 100;
+pub const MAX_PARTICLES: usize = 2000;
+pub const MIN_CURSE_DURATION: f32 = 1.0;
+pub const MAX_CURSE_DURATION: f32 = 15.0;
+pub const MAX_LENGTH_LIMIT: usize = 100;
+const MIN_SCORE_SCALE: f32 = 10.0;
+const MAX_SCORE_SCALE: f32 = 48.0;
+const MAX_HUD_PADDING: f32 = 64.0;
+
+/// O que acontece quando o jogador aperta a direção oposta à atual.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReversePolicy {
+    /// A tecla é ignorada (comportamento padrão).
+    Ignore,
+    /// A cobra morde o próprio pescoço e a partida acaba.
+    Lethal,
+}
+
+/// O que vale quando a cabeça chega a uma maçã numa célula que mata. Isso
+/// acontece quando o anel da arena ou uma parede de rastro se fecha sobre a
+/// maçã: ela só muda de lugar depois do passo seguinte da cobra.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LethalMealPolicy {
+    /// A maçã pontua e só depois a batida é tratada (padrão).
+    ScoreThenDie,
+    /// A batida vem primeiro e a maçã fica onde estava.
+    DieWithoutScore,
+}
+
+/// Teclas que movem a cobra do jogador.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ControlScheme {
+    /// Setas e WASD ao mesmo tempo (padrão).
+    Both,
+    Arrows,
+    Wasd,
+}
+
+/// Como o corpo da cobra do jogador é desenhado.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyStyle {
+    /// Um quadrado por segmento (padrão).
+    Blocks,
+    /// Uma linha contínua pelo centro dos segmentos, com curvas arredondadas.
+    Path,
+}
+
+/// Estilos prontos que a tela de configurações aplica de uma vez, trocando
+/// várias opções juntas.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Preset {
+    /// O celular clássico: tela verde monocromática, blocos separados,
+    /// velocidade fixa e lenta e nada de animações suaves.
+    Nokia3310,
+}
+
+impl Preset {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Preset::Nokia3310 => "Nokia 3310",
+        }
+    }
+
+    /// Troca todas as opções do estilo; as demais ficam como estão.
+    pub fn apply(&self, config: &mut Config) {
+        match *self {
+            Preset::Nokia3310 => {
+                config.theme = NOKIA_THEME.to_string();
+                config.body_style = BodyStyle::Blocks;
+                config.high_visibility = false;
+                config.growth_animation = false;
+                config.smooth_tail = false;
+                config.tongue = false;
+                config.vision_cone = false;
+                config.score_popups = false;
+                config.death_explosion = false;
+                config.wall_pulse = false;
+                config.day_cycle_minutes = 0;
+                config.trail_enabled = false;
+                config.wrap_edges = false;
+                // Um passo a cada 12 quadros: 0,2 s, sem acelerar com a pontuação.
+                config.frames_per_tick = 12;
+            }
+        }
+    }
+}
+
+/// Tamanho da janela escolhido nas configurações.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Resolution {
+    Windowed { width: u32, height: u32 },
+    /// Tela cheia na resolução da área de trabalho.
+    Fullscreen,
+}
+
+/// Resoluções oferecidas nas configurações, na ordem em que são percorridas.
+pub const RESOLUTIONS: &[Resolution] = &[
+    Resolution::Windowed { width: 640, height: 480 },
+    Resolution::Windowed { width: 816, height: 600 },
+    Resolution::Windowed { width: 1280, height: 720 },
+    Resolution::Fullscreen,
+];
+
+/// Opções do jogo que sobrevivem entre execuções.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Desenha o rastro que segue a cabeça da cobra.
+    pub trail_enabled: bool,
+    /// Pontuação com que cada partida começa. Como a velocidade depende da
+    /// pontuação, valores maiores também começam mais rápido.
+    pub starting_score: u32,
+    /// Segundos somados ao intervalo entre movimentos, para começar mais devagar.
+    pub starting_speed_offset: f32,
+    /// Bater na parede não mata: o movimento é cancelado e a cobra espera.
+    pub wall_bounce: bool,
+    /// Quanto a comida tende a nascer longe das paredes (0 = uniforme).
+    pub food_center_bias: f32,
+    /// A arena se fecha periodicamente, empurrando a cobra para o centro.
+    pub shrinking_arena: bool,
+    pub reverse_policy: ReversePolicy,
+    /// Uma virada feita perto do próximo passo anda na hora, em vez de
+    /// esperar por ele. Não deixa a cobra andar mais rápido que o normal.
+    pub instant_turns: bool,
+    /// Com a volta para trás ignorada, a cabeça pisca em vermelho quando a
+    /// tecla é recusada, para mostrar que ela chegou.
+    pub reverse_warning: bool,
+    pub lethal_meal: LethalMealPolicy,
+    /// A comida anda sozinha pela arena, uma célula por vez.
+    pub moving_food: bool,
+    /// Com a comida móvel, desenha as últimas células por onde ela passou.
+    pub food_trail: bool,
+    /// Depois de cada maçã comum, às vezes surge uma especial: bônus, veneno
+    /// ou, no modo festa, maldição. Sem isso elas só surgem pelas teclas de teste.
+    pub special_food: bool,
+    /// Desenha sobre as maçãs especiais um anel que encolhe com o tempo que
+    /// ainda resta até elas sumirem.
+    pub food_countdown: bool,
+    /// Modo festa: chance, de 0.0 a 1.0, de uma maçã especial ser uma
+    /// maldição, que inverte os controles por `curse_duration` segundos.
+    pub curse_chance: f32,
+    pub curse_duration: f32,
+    /// Ajuda: a cada tantos segundos a comida anda uma célula em direção à
+    /// cabeça (0 = desligado). Partidas com ímã contam como alteradas.
+    pub food_magnet_interval: f32,
+    /// Fração das células internas que vira obstáculo no começo de cada
+    /// partida, formando um labirinto aleatório (0 = desligado).
+    pub maze_density: f32,
+    /// Modo difícil: cada célula que a cauda deixa vira parede depois de
+    /// tantos segundos, e a arena vai se enchendo do próprio rastro
+    /// (0 = desligado).
+    pub trail_wall_delay: f32,
+    /// Segundos para comer a próxima maçã na mesma linha ou coluna da
+    /// anterior e continuar a sequência, que dá pontos extras (0 = desligado).
+    pub chain_window: f32,
+    /// Quantas cobras inimigas entram na arena a cada partida.
+    pub enemy_count: u32,
+    /// Volume da música de fundo e dos avisos sonoros, de 0.0 a 1.0.
+    pub volume: f32,
+    pub muted: bool,
+    /// Clique baixo a cada virada da cobra.
+    pub turn_sound: bool,
+    /// Tom de aviso quando a cabeça está colada numa parede e indo na direção dela.
+    pub wall_sound: bool,
+    /// Multiplicador do tamanho das células, ajustado com + e -.
+    pub cell_scale: f32,
+    /// Aproximação da câmera em volta da cabeça, ajustada com [ e ]: mostra
+    /// menos células, maiores, sem mudar o tamanho da grade.
+    pub zoom: f32,
+    /// Pixels físicos por unidade da interface. 0 usa o fator da janela;
+    /// outro valor força uma escala, para testar telas HiDPI.
+    pub ui_scale: f32,
+    /// Com a comida desligada a cobra mantém o tamanho inicial: só navegação.
+    pub food_enabled: bool,
+    /// Restringir o jogador a um conjunto de teclas deixa o outro livre.
+    pub controls: ControlScheme,
+    /// Quantas telas cabem em cada lado da arena; acima de 1 a câmera segue a cobra.
+    pub arena_scale: u32,
+    /// Tamanho da grade imposto por um desafio, no lugar do que a janela daria.
+    /// Vale só enquanto o desafio dura e nunca vai para o arquivo.
+    #[serde(skip)]
+    pub fixed_grid: Option<(i32, i32)>,
+    /// Mapa da arena inteira no canto da tela, quando ela não cabe na janela.
+    pub minimap: bool,
+    /// Barra no canto do HUD que enche conforme a cobra acelera.
+    pub speed_gauge: bool,
+    /// Espalha pela arena pisos que aceleram ou freiam a cobra por alguns passos.
+    pub speed_pads: bool,
+    /// Amostras de antisserrilhado (1 ou 4). Só vale ao abrir o jogo.
+    pub samples: u8,
+    /// Tamanho da janela ao abrir o jogo. Trocar nas configurações vale na
+    /// hora; arrastar a borda da janela não muda este valor.
+    pub resolution: Resolution,
+    /// A faixa da borda vira zona de aviso: dá para passar por ela e só sair
+    /// da grade mata.
+    pub passable_border: bool,
+    /// Nome do tema de cores; se não existir mais, vale o primeiro da lista.
+    pub theme: String,
+    /// As paredes pulsam no ritmo de `pulse_bpm` batidas por minuto.
+    pub wall_pulse: bool,
+    pub pulse_bpm: u32,
+    /// Ao morrer, a cobra explode em partículas antes da tela de fim de jogo.
+    pub death_explosion: bool,
+    /// Limite de partículas vivas; em máquinas fracas, um limite menor evita
+    /// quedas de quadros na explosão de cobras compridas.
+    pub max_particles: usize,
+    /// Sair por uma borda leva ao lado oposto da arena, em vez de matar.
+    pub wrap_edges: bool,
+    /// Onde as primeiras maçãs de cada partida aparecem, em ordem. Depois que
+    /// a lista acaba, a comida volta a ser sorteada. Só editável no arquivo.
+    pub food_script: Vec<GridPos>,
+    /// Segundos até a tela de fim de jogo voltar sozinha ao menu (0 = esperar
+    /// uma tecla). Pensado para máquinas de demonstração.
+    pub auto_return_delay: u32,
+    /// O segmento novo cresce aos poucos em vez de aparecer de uma vez.
+    pub growth_animation: bool,
+    /// No estilo de blocos, a ponta da cauda recolhe deslizando durante o
+    /// passo, em vez de sumir de uma vez.
+    pub smooth_tail: bool,
+    /// De tempos em tempos a cobra põe a língua para fora.
+    pub tongue: bool,
+    /// Um cone claro sai da cabeça na direção em que ela olha. Só enfeite.
+    pub vision_cone: bool,
+    /// Um "+N" sobe da célula onde a cobra ganhou ou perdeu pontos.
+    pub score_popups: bool,
+    /// No menu, a cobra continua andando sozinha atrás das opções, só de enfeite.
+    pub menu_preview: bool,
+    /// Minutos de um ciclo de noite, amanhecer, dia e entardecer, que tinge o
+    /// chão por cima das cores do tema (0 = desligado).
+    pub day_cycle_minutes: u32,
+    pub body_style: BodyStyle,
+    /// Direcional desenhado na tela, para jogar com toque ou mouse. Liga
+    /// sozinho, até o jogo fechar, quando a tela é tocada.
+    pub touch_dpad: bool,
+    /// Texto da pontuação no HUD; `{}` marca onde entra o número. Junto com
+    /// `score_scale`, só editável no arquivo.
+    pub score_format: String,
+    pub score_scale: f32,
+    /// Distância, em unidades da interface, entre o HUD e a borda da arena.
+    /// Só editável no arquivo.
+    pub hud_padding: f32,
+    /// Quantas células à frente da cabeça são marcadas, mostrando por onde a
+    /// cobra passa se seguir reto (0 = desligado).
+    pub projection_length: u32,
+    /// Com "Jogar" selecionado no menu, esquerda ou direita começam a partida
+    /// com a cobra virada para esse lado. Cima, baixo e Tab continuam navegando.
+    pub direction_starts: bool,
+    /// Alta visibilidade: fundo preto, paredes brancas, contornos grossos na
+    /// cobra, comida ocupando a célula inteira e textos do HUD maiores.
+    pub high_visibility: bool,
+    /// Vidas por partida: bater numa parede ou obstáculo gasta uma e leva a
+    /// cobra de volta ao centro. Com 0, a batida mata na hora.
+    pub wall_lives: u32,
+    /// Segundos do começo da partida em que as paredes seguram a cobra em vez
+    /// de matá-la, para perdoar a primeira tecla errada (0 = desligado).
+    pub start_grace: f32,
+    /// Com um valor acima de 0, a cobra anda um passo a cada tantos quadros
+    /// e os cronômetros contam quadros, não segundos: a partida fica idêntica
+    /// em qualquer máquina, mas a velocidade não sobe com a pontuação.
+    pub frames_per_tick: u32,
+    /// Tamanho máximo da cobra. Ao atingi-lo, comer continua pontuando, mas a
+    /// cobra para de crescer. `None` deixa crescer à vontade.
+    pub max_length: Option<usize>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            trail_enabled: true,
+            starting_score: 0,
+            starting_speed_offset: 0.0,
+            wall_bounce: false,
+            food_center_bias: 0.0,
+            shrinking_arena: false,
+            reverse_policy: ReversePolicy::Ignore,
+            instant_turns: false,
+            reverse_warning: true,
+            lethal_meal: LethalMealPolicy::ScoreThenDie,
+            moving_food: false,
+            food_trail: false,
+            special_food: false,
+            food_countdown: false,
+            curse_chance: 0.0,
+            curse_duration: 5.0,
+            food_magnet_interval: 0.0,
+            maze_density: 0.0,
+            chain_window: 0.0,
+            trail_wall_delay: 0.0,
+            enemy_count: 0,
+            volume: 0.5,
+            muted: false,
+            turn_sound: false,
+            wall_sound: false,
+            cell_scale: 1.0,
+            zoom: 1.0,
+            ui_scale: 0.0,
+            food_enabled: true,
+            controls: ControlScheme::Both,
+            arena_scale: 1,
+            fixed_grid: None,
+            minimap: true,
+            speed_gauge: false,
+            speed_pads: false,
+            samples: 4,
+            resolution: Resolution::Windowed { width: 816, height: 600 },
+            passable_border: false,
+            theme: "Clássico".to_string(),
+            wall_pulse: false,
+            pulse_bpm: 120,
+            death_explosion: true,
+            max_particles: 1000,
+            wrap_edges: false,
+            food_script: Vec::new(),
+            auto_return_delay: 0,
+            growth_animation: true,
+            smooth_tail: false,
+            tongue: true,
+            vision_cone: false,
+            score_popups: true,
+            menu_preview: false,
+            day_cycle_minutes: 0,
+            body_style: BodyStyle::Blocks,
+            touch_dpad: false,
+            score_format: "{}".to_string(),
+            score_scale: 20.0,
+            hud_padding: 10.0,
+            projection_length: 0,
+            direction_starts: false,
+            high_visibility: false,
+            wall_lives: 0,
+            start_grace: 0.0,
+            frames_per_tick: 0,
+            max_length: None,
+        }
+    }
+}
+
+impl Config {
+    /// Indica se a partida foi alterada em relação às regras padrão.
+    pub fn is_handicapped(&self) -> bool {
+        self.starting_score != 0 || self.starting_speed_offset != 0.0 || self.food_magnet_interval > 0.0
+    }
+
+    /// Dimensões da grade, em células, para uma janela deste tamanho, ou as
+    /// do desafio em andamento.
+    pub fn grid_size(&self, screen_w: f32, screen_h: f32, block_size: f32) -> (i32, i32) {
+        if let Some(grid) = self.fixed_grid {
+            return grid;
+        }
+        let cell_size = block_size * self.cell_scale;
+        let scale = self.arena_scale as i32;
+        ((screen_w / cell_size) as i32 * scale, (screen_h / cell_size) as i32 * scale)
+    }
+
+    /// Escala da interface numa janela com este fator de escala.
+    pub fn effective_ui_scale(&self, window_scale: f32) -> f32 {
+        if self.ui_scale > 0.0 { self.ui_scale } else { window_scale }
+    }
+
+    /// Texto da pontuação no HUD, montado a partir de `score_format`.
+    pub fn score_label(&self, score: u32) -> String {
+        self.score_format.replacen("{}", &score.to_string(), 1)
+    }
+
+    /// Volume efetivo da música, já considerando o mudo.
+    pub fn music_volume(&self) -> f32 {
+        if self.muted { 0.0 } else { self.volume }
+    }
+
+    /// Amostras pedidas à janela. Valores que a ggez não aceita viram 1.
+    pub fn num_samples(&self) -> NumSamples {
+        NumSamples::try_from(self.samples).unwrap_or(NumSamples::One)
+    }
+
+    /// Modo da janela para a resolução escolhida.
+    pub fn window_mode(&self) -> WindowMode {
+        let mode = WindowMode::default().resizable(true);
+        match self.resolution {
+            Resolution::Windowed { width, height } => mode.dimensions(width as f32, height as f32),
+            Resolution::Fullscreen => mode.fullscreen_type(FullscreenType::Desktop),
+        }
+    }
+
+    /// Carrega a configuração salva. Se o arquivo não existir ou estiver
+    /// inválido, usa os valores padrão. Recebe o sistema de arquivos em vez do
+    /// contexto porque a janela é criada com base nela.
+    pub fn load(fs: &Filesystem) -> Config {
+        let mut config: Config = fs
+            .open(CONFIG_PATH)
+            .ok()
+            .and_then(|file| serde_json::from_reader(file).ok())
+            .unwrap_or_default();
+        config.clamp_ranges();
+        config
+    }
+
+    /// Traz de volta às faixas da tela de configurações os valores que um
+    /// arquivo editado à mão deixou fora delas.
+    pub fn clamp_ranges(&mut self) {
+        // Um arquivo editado à mão não pode deixar a grade com tamanho inválido.
+        self.cell_scale = self.cell_scale.clamp(MIN_CELL_SCALE, MAX_CELL_SCALE);
+        self.arena_scale = self.arena_scale.clamp(1, MAX_ARENA_SCALE);
+        self.zoom = self.zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        if self.ui_scale != 0.0 {
+            self.ui_scale = self.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        }
+        self.score_scale = self.score_scale.clamp(MIN_SCORE_SCALE, MAX_SCORE_SCALE);
+        self.hud_padding = self.hud_padding.clamp(0.0, MAX_HUD_PADDING);
+        // O resto volta às faixas que a tela de configurações permite.
+        self.max_length = self.max_length.map(|len| len.clamp(MIN_MAX_LENGTH, MAX_LENGTH_LIMIT));
+        self.food_center_bias = self.food_center_bias.clamp(0.0, MAX_FOOD_CENTER_BIAS);
+        self.starting_score = self.starting_score.min(MAX_STARTING_SCORE);
+        self.starting_speed_offset = self.starting_speed_offset.clamp(0.0, MAX_SPEED_OFFSET);
+        self.pulse_bpm = self.pulse_bpm.clamp(MIN_PULSE_BPM, MAX_PULSE_BPM);
+        self.volume = self.volume.clamp(0.0, 1.0);
+        self.auto_return_delay = self.auto_return_delay.min(MAX_AUTO_RETURN_DELAY);
+        self.projection_length = self.projection_length.min(MAX_PROJECTION_LENGTH);
+        self.wall_lives = self.wall_lives.min(MAX_WALL_LIVES);
+        self.start_grace = self.start_grace.clamp(0.0, MAX_START_GRACE);
+        self.frames_per_tick = self.frames_per_tick.min(MAX_FRAMES_PER_TICK);
+        self.food_magnet_interval = self.food_magnet_interval.clamp(0.0, MAX_MAGNET_INTERVAL);
+        self.day_cycle_minutes = self.day_cycle_minutes.min(MAX_DAY_CYCLE_MINUTES);
+        self.maze_density = self.maze_density.clamp(0.0, MAX_MAZE_DENSITY);
+        self.trail_wall_delay = self.trail_wall_delay.clamp(0.0, MAX_TRAIL_WALL_DELAY);
+        self.chain_window = self.chain_window.clamp(0.0, MAX_CHAIN_WINDOW);
+        self.curse_chance = self.curse_chance.clamp(0.0, 1.0);
+        self.curse_duration = self.curse_duration.clamp(MIN_CURSE_DURATION, MAX_CURSE_DURATION);
+        self.max_particles = self.max_particles.clamp(MIN_PARTICLES, MAX_PARTICLES);
+        self.enemy_count = self.enemy_count.min(MAX_ENEMIES);
+    }
+
+    pub fn save(&self, ctx: &Context) -> GameResult {
+        let file = ctx.fs.create(CONFIG_PATH)?;
+        serde_json::to_writer_pretty(file, self)
+            .map_err(|e| ggez::GameError::CustomError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_inside_the_ranges() {
+        let mut config = Config::default();
+        config.clamp_ranges();
+        let json = |c: &Config| serde_json::to_string(c).unwrap();
+        assert_eq!(json(&config), json(&Config::default()));
+    }
+
+    #[test]
+    fn clamp_ranges_matches_the_settings_limits() {
+        let mut config = Config {
+            pulse_bpm: 1_000,
+            volume: 7.0,
+            curse_chance: -1.0,
+            maze_density: 0.9,
+            frames_per_tick: 500,
+            max_particles: 1,
+            max_length: Some(10_000),
+            ..Config::default()
+        };
+        config.clamp_ranges();
+        assert_eq!(config.pulse_bpm, MAX_PULSE_BPM);
+        assert_eq!(config.volume, 1.0);
+        assert_eq!(config.curse_chance, 0.0);
+        assert_eq!(config.maze_density, MAX_MAZE_DENSITY);
+        assert_eq!(config.frames_per_tick, MAX_FRAMES_PER_TICK);
+        assert_eq!(config.max_particles, MIN_PARTICLES);
+        assert_eq!(config.max_length, Some(MAX_LENGTH_LIMIT));
+    }
+}
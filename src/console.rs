@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+
+// Linhas de resposta guardadas; as mais antigas vão sendo descartadas.
+const MAX_OUTPUT_LINES: usize = 6;
+
+/// O que pode ser criado na arena com `spawn`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SpawnKind {
+    Bonus,
+    Poison,
+    Obstacle,
+    Enemy,
+}
+
+/// Um comando do console, já com os argumentos validados.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Command {
+    /// Fixa o intervalo entre movimentos, em segundos; `None` volta ao normal.
+    Speed(Option<f32>),
+    Grow(u32),
+    Theme(String),
+    Spawn(SpawnKind),
+    Help,
+}
+
+/// Texto mostrado pelo comando `help`.
+pub const HELP: &str = "speed <s>|off  grow <n>  theme <nome>  spawn bonus|poison|obstacle|enemy";
+
+/// Interpreta uma linha digitada. O erro é a mensagem a mostrar no console.
+pub fn parse(line: &str) -> Result<Command, String> {
+    let mut words = line.split_whitespace();
+    let name = words.next().ok_or_else(|| "Digite um comando".to_string())?;
+    let rest: Vec<&str> = words.collect();
+    match (name, rest.as_slice()) {
+        ("speed", ["off"]) => Ok(Command::Speed(None)),
+        ("speed", [value]) => match value.parse::<f32>() {
+            Ok(secs) if secs > 0.0 => Ok(Command::Speed(Some(secs))),
+            _ => Err(format!("Intervalo inválido: {}", value)),
+        },
+        ("grow", [value]) => value
+            .parse()
+            .map(Command::Grow)
+            .map_err(|_| format!("Quantidade inválida: {}", value)),
+        ("theme", [_, ..]) => Ok(Command::Theme(rest.join(" "))),
+        ("spawn", [kind]) => {
+            let kind = match *kind {
+                "bonus" => SpawnKind::Bonus,
+                "poison" => SpawnKind::Poison,
+                "obstacle" => SpawnKind::Obstacle,
+                "enemy" => SpawnKind::Enemy,
+                _ => return Err(format!("Não sei criar \"{}\"", kind)),
+            };
+            Ok(Command::Spawn(kind))
+        }
+        ("help", []) => Ok(Command::Help),
+        ("speed" | "grow" | "theme" | "spawn", _) => Err(format!("Argumentos errados para {}; veja help", name)),
+        _ => Err(format!("Comando desconhecido: {}", name)),
+    }
+}
+
+/// Linha sendo digitada e respostas recentes do console de depuração.
+#[derive(Default)]
+pub struct Console {
+    pub open: bool,
+    pub input: String,
+    pub output: VecDeque<String>,
+}
+
+impl Console {
+    /// Acrescenta uma linha de resposta.
+    pub fn print(&mut self, line: impl Into<String>) {
+        if self.output.len() == MAX_OUTPUT_LINES {
+            self.output.pop_front();
+        }
+        self.output.push_back(line.into());
+    }
+
+    /// Esvazia a linha digitada, devolvendo o comando e ecoando-o nas respostas.
+    pub fn take_input(&mut self) -> String {
+        let line = std::mem::take(&mut self.input);
+        self.print(format!("> {}", line));
+        line
+    }
+}
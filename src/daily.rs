@@ -0,0 +1,44 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::challenge::Challenge;
+use crate::config::Config;
+
+/// Tamanho da arena do desafio, o mesmo em qualquer janela.
+pub const GRID: (i32, i32) = (32, 24);
+
+/// Dias desde 1970-01-01, em UTC, para que todos compartilhem o mesmo desafio.
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / 86_400)
+}
+
+/// Semente do desafio de um dia. Espalha os bits para que dias vizinhos não
+/// gerem partidas parecidas.
+pub fn seed(day: u64) -> u64 {
+    let mut x = day.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// As regras do desafio de um dia: as padrão, com a semente do dia e a grade
+/// fixa. A configuração de quem joga não entra.
+pub fn challenge(day: u64) -> Challenge {
+    Challenge::from_config(seed(day), GRID, &Config::default())
+}
+
+/// Data do dia no formato AAAA-MM-DD.
+pub fn date_label(day: u64) -> String {
+    // Conversão de dias para data civil (algoritmo de Howard Hinnant).
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
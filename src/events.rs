@@ -0,0 +1,35 @@
+use crate::grid::Direction;
+use crate::{DeathCause, SpecialFoodKind};
+
+/// Acontecimentos da partida. A lógica do jogo só os empilha; quem hospeda o
+/// jogo esvazia a fila a cada quadro e decide como reagir (placar, sons, logs).
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    FoodEaten { score: u32 },
+    SpecialFoodEaten { kind: SpecialFoodKind, score: u32 },
+    Died { score: u32, cause: DeathCause },
+    /// A cobra trocou de direção neste passo.
+    Turned { direction: Direction },
+    /// Seguindo em frente, a cabeça entra numa parede no próximo passo.
+    NearWall,
+}
+
+impl GameEvent {
+    /// Descrição curta para a sobreposição de depuração.
+    pub fn describe(&self) -> String {
+        match self {
+            GameEvent::FoodEaten { score } => format!("comeu ({} pts)", score),
+            GameEvent::SpecialFoodEaten { kind, score } => {
+                let name = match kind {
+                    SpecialFoodKind::Bonus => "bônus",
+                    SpecialFoodKind::Poison => "veneno",
+                    SpecialFoodKind::Curse => "maldição",
+                };
+                format!("comeu {} ({} pts)", name, score)
+            }
+            GameEvent::Died { score, cause } => format!("morreu ({} pts): {}", score, cause.message()),
+            GameEvent::Turned { direction } => format!("virou: {:?}", direction),
+            GameEvent::NearWall => "parede à frente".to_string(),
+        }
+    }
+}
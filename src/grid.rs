@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+/// Direções em que a cobra pode andar.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    /// Deslocamento (x, y) de um passo nesta direção.
+    pub fn offset(&self) -> (i32, i32) {
+        match *self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match *self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+/// Uma célula da grade, em coordenadas de célula (não de pixel).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+pub struct GridPos {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl GridPos {
+    pub const fn new(x: i32, y: i32) -> GridPos {
+        GridPos { x, y }
+    }
+
+    /// A célula vizinha na direção dada.
+    pub fn neighbor(self, direction: Direction) -> GridPos {
+        let (dx, dy) = direction.offset();
+        GridPos::new(self.x + dx, self.y + dy)
+    }
+
+    /// A mesma posição trazida para dentro de uma grade `width` x `height`,
+    /// dando a volta pelas bordas.
+    pub fn wrapped(self, width: i32, height: i32) -> GridPos {
+        GridPos::new(self.x.rem_euclid(width), self.y.rem_euclid(height))
+    }
+
+    /// Distância em passos, sem diagonais.
+    pub fn manhattan(self, other: GridPos) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbor_moves_one_cell() {
+        let pos = GridPos::new(3, 4);
+        assert_eq!(pos.neighbor(Direction::Up), GridPos::new(3, 3));
+        assert_eq!(pos.neighbor(Direction::Down), GridPos::new(3, 5));
+        assert_eq!(pos.neighbor(Direction::Left), GridPos::new(2, 4));
+        assert_eq!(pos.neighbor(Direction::Right), GridPos::new(4, 4));
+        for direction in Direction::ALL {
+            assert_eq!(pos.neighbor(direction).neighbor(direction.opposite()), pos);
+        }
+    }
+
+    #[test]
+    fn wrapped_comes_back_from_every_edge() {
+        assert_eq!(GridPos::new(2, 3).wrapped(10, 8), GridPos::new(2, 3));
+        assert_eq!(GridPos::new(-1, 3).wrapped(10, 8), GridPos::new(9, 3));
+        assert_eq!(GridPos::new(10, 3).wrapped(10, 8), GridPos::new(0, 3));
+        assert_eq!(GridPos::new(4, -1).wrapped(10, 8), GridPos::new(4, 7));
+        assert_eq!(GridPos::new(4, 8).wrapped(10, 8), GridPos::new(4, 0));
+        assert_eq!(GridPos::new(-11, 17).wrapped(10, 8), GridPos::new(9, 1));
+    }
+}
@@ -0,0 +1,69 @@
+use ggez::{Context, GameResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const LEADERBOARD_PATH: &str = "/leaderboard.json";
+const MAX_ENTRIES: usize = 10;
+const MAX_DAILY_DAYS: usize = 30;
+
+/// Uma partida registrada no placar.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub score: u32,
+    /// A partida começou com pontuação ou velocidade alteradas nas configurações.
+    #[serde(default)]
+    pub handicapped: bool,
+}
+
+/// As melhores partidas, ordenadas da maior para a menor pontuação.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Leaderboard {
+    pub entries: Vec<Entry>,
+    /// Melhor pontuação do desafio diário, por dia (contado desde 1970-01-01).
+    pub daily: BTreeMap<u64, u32>,
+}
+
+impl Leaderboard {
+    pub fn load(ctx: &Context) -> Leaderboard {
+        ctx.fs
+            .open(LEADERBOARD_PATH)
+            .ok()
+            .and_then(|file| serde_json::from_reader(file).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, ctx: &Context) -> GameResult {
+        let file = ctx.fs.create(LEADERBOARD_PATH)?;
+        serde_json::to_writer_pretty(file, self)
+            .map_err(|e| ggez::GameError::CustomError(e.to_string()))
+    }
+
+    /// Esvazia o placar e grava o arquivo vazio por cima do antigo, o que
+    /// também funciona quando ainda não existe arquivo nenhum.
+    pub fn reset(&mut self, ctx: &Context) -> GameResult {
+        *self = Leaderboard::default();
+        self.save(ctx)
+    }
+
+    /// Insere a partida na posição correta, descartando o que passar do limite.
+    pub fn record(&mut self, entry: Entry) {
+        let index = self
+            .entries
+            .iter()
+            .position(|e| e.score < entry.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(index, entry);
+        self.entries.truncate(MAX_ENTRIES);
+    }
+
+    /// Guarda a pontuação do desafio do dia se ela for a melhor desse dia.
+    /// Só os dias mais recentes são mantidos.
+    pub fn record_daily(&mut self, day: u64, score: u32) {
+        let best = self.daily.entry(day).or_insert(0);
+        *best = (*best).max(score);
+        while self.daily.len() > MAX_DAILY_DAYS {
+            self.daily.pop_first();
+        }
+    }
+}
@@ -1,96 +1,351 @@
-use ggez::conf::{WindowMode, WindowSetup};
-use ggez::event::{self, EventHandler};
+use ggez::conf::{NumSamples, WindowSetup};
+use ggez::event::winit_event::TouchPhase;
+use ggez::event::{self, EventHandler, MouseButton};
+use ggez::filesystem::Filesystem;
 use ggez::glam::Vec2;
-use ggez::graphics::{self, Color, DrawMode, DrawParam, Drawable, Mesh, Rect, Text};
-use ggez::input::keyboard::{KeyCode, KeyInput};
+use ggez::graphics::{
+    self, Color, DrawMode, DrawParam, Drawable, LineCap, LineJoin, Mesh, MeshBuilder, Rect, StrokeOptions, Text,
+    TextFragment,
+};
+use ggez::input::keyboard::{KeyCode, KeyInput, KeyMods};
 use ggez::{Context, ContextBuilder, GameResult};
-use rand::{thread_rng, Rng};
-use std::collections::LinkedList;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashSet, LinkedList, VecDeque};
+use serde::{Deserialize, Serialize};
 use std::path;
+use std::time::Instant;
+
+mod ai;
+mod benchmark;
+mod board;
+mod challenge;
+mod cheats;
+mod config;
+mod console;
+mod daily;
+mod events;
+mod grid;
+mod leaderboard;
+mod music;
+mod particles;
+mod recording;
+mod settings;
+mod sounds;
+mod stats;
+mod theme;
+
+use board::{Board, RaceResult};
+use challenge::Challenge;
+use cheats::Cheat;
+use console::{Command, Console, SpawnKind};
+use config::{
+    BodyStyle, Config, ControlScheme, LethalMealPolicy, MAX_CELL_SCALE, MAX_ZOOM, MIN_CELL_SCALE, MIN_ZOOM, ReversePolicy,
+};
+use events::GameEvent;
+use grid::{Direction, GridPos};
+use leaderboard::{Entry, Leaderboard};
+use music::{Music, Track};
+use particles::Particles;
+use recording::Recording;
+use settings::{SETTINGS, Setting};
+use sounds::{Cue, Sounds};
+use stats::Stats;
+use theme::{EyeStyle, FoodShape, GridStyle, Theme};
 
 // --- CONSTANTES DO JOGO ---
 const BLOCK_SIZE: f32 = 24.0;
 const MAIN_FONT: &str = "main_font"; // Nome para registrar e usar a fonte.
+const DEBUG_FONT: &str = "LiberationMono-Regular"; // Fonte embutida da ggez, dos textos de depuração.
+const GAME_ID: &str = "snake_rust";
+const AUTHOR: &str = "Gemini";
+const TRAIL_LENGTH: usize = 6; // Quantas células deixadas para trás pela cauda o rastro mostra.
+const SLOW_MOTION_FACTOR: f32 = 4.0; // Quantas vezes mais lento fica o movimento em câmera lenta.
+const RING_SHRINK_INTERVAL: f32 = 15.0; // Segundos entre cada fechamento da arena.
+const MIN_ARENA_SIZE: i32 = 6; // A arena para de encolher ao chegar neste tamanho interno.
+const SPECIAL_FOOD_CHANCE: f32 = 0.2; // Chance de surgir uma maçã especial ao comer uma normal.
+const SPECIAL_FOOD_LIFETIME: f32 = 6.0; // Segundos até a maçã especial sumir.
+const COUNTDOWN_SEGMENTS: usize = 24; // Segmentos do anel de contagem com o tempo cheio.
+const BONUS_POINTS: u32 = 5;
+const POISON_SHRINK: usize = 2; // Segmentos perdidos ao comer a maçã envenenada.
+const MIN_SNAKE_LENGTH: usize = 3;
+const SNAKE_START: GridPos = GridPos::new(3, 2); // Posição inicial da cabeça.
+const BASE_INTERVAL: f32 = 0.15; // Segundos entre passos no começo da partida.
+const MIN_INTERVAL: f32 = 0.05; // O passo mais rápido a que a pontuação leva.
+const FOOD_MOVE_INTERVAL: f32 = 0.6; // Segundos entre os passos da comida no modo alvo móvel.
+const CELL_SCALE_STEP: f32 = 0.25;
+const ZOOM_STEP: f32 = 0.25;
+const SETTINGS_VISIBLE: usize = 7; // Opções mostradas de uma vez na tela de configurações.
+const MAX_QUEUED_TURNS: usize = 2; // Viradas guardadas para os próximos passos.
+const ENEMY_SPAWN_DISTANCE: i32 = 6; // Distância mínima da cabeça do jogador ao surgir um inimigo.
+const INTRO_DURATION: f32 = 0.5; // Segundos da animação da grade no começo da partida.
+const MINIMAP_SIZE: f32 = 160.0; // Lado máximo do minimapa, em pixels da tela.
+const SPEED_GAUGE_SIZE: Vec2 = Vec2::new(120.0, 10.0); // Medidor de velocidade, em pixels da tela.
+const SPEED_PADS_PER_KIND: usize = 2; // Pisos de cada tipo espalhados por partida.
+const MAZE_ATTEMPTS: usize = 20; // Sorteios do labirinto aleatório antes de desistir dele.
+const MAZE_CLEARANCE: i32 = 3; // Distância da cabeça inicial em que não nascem obstáculos.
+const SPEED_PAD_TICKS: u32 = 12; // Passos que o efeito de um piso dura.
+const CHEAT_FLASH_DURATION: f32 = 2.0; // Segundos que a confirmação de um código fica na tela.
+const REPLAY_FRAMES: usize = 10; // Passos gravados para rebobinar ao morrer.
+const REPLAY_DURATION: f32 = 1.0; // Segundos que o rebobinamento leva.
+const TEXT_SHADOW_OFFSET: f32 = 2.0; // Deslocamento, em pixels, da sombra dos textos.
+const WALL_PULSE_STRENGTH: f32 = 0.5; // Quanto as paredes clareiam no pico de cada batida.
+// Fator aplicado às cores do fundo na noite, no amanhecer, no dia e no entardecer.
+const DAY_PHASES: [[f32; 3]; 4] = [[0.55, 0.6, 0.85], [1.1, 0.9, 0.8], [1.25, 1.25, 1.2], [1.15, 0.8, 0.65]];
+const EXPLOSION_PARTICLES: usize = 8; // Partículas por segmento quando a cobra explode.
+const EXPLOSION_SPEED: f32 = 120.0; // Velocidade máxima das partículas, em unidades do mundo por segundo.
+const EXPLOSION_LIFETIME: f32 = 0.8; // Segundos até as partículas sumirem.
+const DASH_CHARGE_TIME: f32 = 0.3; // Segundos segurando a tecla até a investida ficar carregada.
+const DASH_COOLDOWN: f32 = 3.0; // Segundos de espera entre duas investidas.
+const FRAME_BUDGET: f32 = 0.016; // Segundos que um quadro pode levar sem destoar de 60 FPS.
+const FRAME_TIME_SMOOTHING: f32 = 0.1; // Peso de cada quadro novo nas médias de tempo.
+const GROWTH_DURATION: f32 = 0.2; // Segundos que o segmento novo leva para atingir o tamanho cheio.
+const HIGH_VIS_OUTLINE: f32 = 3.0; // Espessura dos contornos no modo de alta visibilidade.
+const HIGH_VIS_TEXT_SCALE: f32 = 1.5; // Quanto os textos do HUD crescem nesse modo.
+const MAX_CHALLENGE_CODE_LEN: usize = 96; // Caracteres aceitos no campo do código de desafio.
+const TAIL_BRIGHTNESS: f32 = 0.45; // Brilho da ponta da cauda, em relação ao corpo, com o degradê do tema.
+const FOOD_TRAIL_LENGTH: usize = 5; // Células antigas da comida móvel guardadas para o rastro.
+const FAST_FORWARD_TICKS: u32 = 100; // Passos dados de uma vez pela tecla de avanço rápido da depuração.
+const REVERSE_WARNING_DURATION: f32 = 0.25; // Segundos que a cabeça fica vermelha ao recusar uma volta.
+const TONGUE_INTERVAL: f32 = 2.5; // Segundos entre o começo de duas linguadas.
+const TONGUE_OUT_TIME: f32 = 0.25; // Segundos que a língua fica para fora em cada linguada.
+const POPUP_DURATION: f32 = 0.8; // Segundos que o "+N" de uma maçã leva para sumir.
+const POPUP_RISE: f32 = 24.0; // Quanto o "+N" sobe até sumir, em pixels do mundo.
+const VISION_CONE_LENGTH: f32 = 4.0; // Alcance do cone de visão, em células.
+const VISION_CONE_SPREAD: f32 = 1.5; // Meia largura do cone na ponta, em células.
+const INSTANT_TURN_THRESHOLD: f32 = 0.5; // Fração do intervalo após a qual uma virada anda na hora.
+const STEP_IDLE_DELAY: f32 = 3.0; // Segundos sem passos no modo passo a passo até o aviso de parado.
+const FIXED_FRAME_TIME: f32 = 1.0 / 60.0; // Quanto cada quadro conta no ritmo por quadros.
+const INVINCIBILITY_DURATION: f32 = 2.0; // Segundos sem colisões com corpos depois de perder uma vida.
+const DPAD_BUTTON_SIZE: f32 = 56.0; // Lado, em pixels, de cada botão do direcional na tela.
 
 // --- ESTADOS DO JOGO ---
 enum GameMode {
     Menu,
+    Settings,
+    Leaderboard,
+    Stats,
+    Help,
+    // Campo para digitar o código de um desafio compartilhado.
+    ImportChallenge,
+    // Corrida de dois jogadores, cada um na própria metade da tela.
+    SplitScreen,
     Playing,
+    // Rebobina os últimos movimentos da cobra antes da tela de fim de jogo.
+    DeathReplay,
     GameOver,
 }
 
+// --- ENTRADAS DO MENU PRINCIPAL ---
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum MenuItem {
+    Play,
+    Daily,
+    SplitScreen,
+    ImportChallenge,
+    Settings,
+    Leaderboard,
+    Stats,
+    Help,
+    Quit,
+}
+
+const MENU_ITEMS: &[MenuItem] = &[
+    MenuItem::Play,
+    MenuItem::Daily,
+    MenuItem::SplitScreen,
+    MenuItem::ImportChallenge,
+    MenuItem::Settings,
+    MenuItem::Leaderboard,
+    MenuItem::Stats,
+    MenuItem::Help,
+    MenuItem::Quit,
+];
+
+impl MenuItem {
+    fn label(&self) -> &'static str {
+        match *self {
+            MenuItem::Play => "Jogar",
+            MenuItem::Daily => "Desafio diário",
+            MenuItem::SplitScreen => "Tela dividida",
+            MenuItem::ImportChallenge => "Importar desafio",
+            MenuItem::Settings => "Configurações",
+            MenuItem::Leaderboard => "Placar",
+            MenuItem::Stats => "Estatísticas",
+            MenuItem::Help => "Ajuda",
+            MenuItem::Quit => "Sair",
+        }
+    }
+}
+
 // --- ESTRUTURAS E ENUMS DO JOGO ---
 
+/// O que encerrou a partida, mostrado na tela de fim de jogo.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum DeathCause {
+    Wall,
+    Obstacle,
+    Tail,
+    Enemy,
+    ArenaClosed,
+    Reversed,
+    /// Não sobrou nenhuma célula alcançável para a comida.
+    Stalemate,
+}
+
+impl DeathCause {
+    const ALL: [DeathCause; 7] = [
+        DeathCause::Wall,
+        DeathCause::Obstacle,
+        DeathCause::Tail,
+        DeathCause::Enemy,
+        DeathCause::ArenaClosed,
+        DeathCause::Reversed,
+        DeathCause::Stalemate,
+    ];
+
+    fn message(&self) -> &'static str {
+        match *self {
+            DeathCause::Wall => "Bateu na parede",
+            DeathCause::Obstacle => "Bateu num obstáculo",
+            DeathCause::Tail => "Mordeu o rabo",
+            DeathCause::Enemy => "Trombou com uma cobra inimiga",
+            DeathCause::ArenaClosed => "Esmagada pela arena",
+            DeathCause::Reversed => "Tentou voltar para trás",
+            DeathCause::Stalemate => "Sem saída: a comida não tinha onde nascer",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum SpecialFoodKind {
+    /// Vale mais pontos que a maçã comum.
+    Bonus,
+    /// Encolhe a cobra e tira um ponto.
+    Poison,
+    /// Inverte os controles por `curse_duration` segundos.
+    Curse,
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
-enum Direction {impl Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+enum PadKind {
+    /// Piso verde: encurta o intervalo entre passos.
+    Fast,
+    /// Piso azul: alonga o intervalo entre passos.
+    Slow,
 }
 
-impl Direction {
-    fn opposite(&self) -> Direction {
+impl PadKind {
+    /// Multiplicador aplicado ao intervalo entre passos.
+    fn interval_factor(&self) -> f32 {
         match *self {
-            Direction::Up => Direction::Down,
-            Direction::Down => Direction::Up,
-            Direction::Left => Direction::Right,
-            Direction::Right => Direction::Left,
+            PadKind::Fast => 0.6,
+            PadKind::Slow => 1.6,
         }
     }
 }
 
+/// Piso que não mata, mas muda a velocidade de quem passa por cima.
+#[derive(Debug, Clone)]
+struct SpeedPad {
+    kind: PadKind,
+    pos: GridPos,
+}
+
+/// Maçã temporária que aparece de vez em quando além da comida comum.
 #[derive(Debug, Clone)]
-struct Block {
-    x: i32,
-    y: i32,
+struct SpecialFood {
+    kind: SpecialFoodKind,
+    pos: GridPos,
+    // Segundos restantes até sumir.
+    ttl: f32,
+}
+
+/// Texto "+N" que sobe e some a partir da célula onde a pontuação mudou.
+struct ScorePopup {
+    cell: GridPos,
+    value: i32,
+    // Segundos restantes, de `POPUP_DURATION` até zero.
+    ttl: f32,
 }
 
 struct Snake {
     direction: Direction,
-    body: LinkedList<Block>,
-    tail: Option<Block>,
+    // Direção do último movimento de fato. Difere de `direction` quando uma
+    // virada contra a parede segura é cancelada e a cobra fica parada.
+    last_moved: Direction,
+    body: LinkedList<GridPos>,
+    tail: Option<GridPos>,
 }
 
 impl Snake {
-    fn new(x: i32, y: i32) -> Snake {
-        let mut body: LinkedList<Block> = LinkedList::new();
-        body.push_back(Block { x, y });
-        body.push_back(Block { x: x - 1, y });
-        body.push_back(Block { x: x - 2, y });
+    /// Cobra de três segmentos com a cabeça em `head`, virada para `direction`.
+    fn new(head: GridPos, direction: Direction) -> Snake {
+        let mut body: LinkedList<GridPos> = LinkedList::new();
+        body.push_back(head);
+        body.push_back(head.neighbor(direction.opposite()));
+        body.push_back(head.neighbor(direction.opposite()).neighbor(direction.opposite()));
 
         Snake {
-            direction: Direction::Right,
+            direction,
+            last_moved: direction,
             body,
             tail: None,
         }
     }
 
+    fn head(&self) -> GridPos {
+        *self.body.front().expect("A cobra não tem corpo.")
+    }
+
+    /// Posição que a cabeça ocupará no próximo movimento.
+    fn next_head(&self) -> GridPos {
+        self.head().neighbor(self.direction)
+    }
+
     fn move_forward(&mut self) {
-        let head = self.body.front().expect("A cobra não tem corpo.").clone();
-        let new_head = match self.direction {
-            Direction::Up => Block { x: head.x, y: head.y - 1 },
-            Direction::Down => Block { x: head.x, y: head.y + 1 },
-            Direction::Left => Block { x: head.x - 1, y: head.y },
-            Direction::Right => Block { x: head.x + 1, y: head.y },
-        };
+        self.move_to(self.next_head());
+    }
+
+    /// Coloca a cabeça em `new_head`, que não precisa ser vizinha da atual
+    /// (ao atravessar uma borda, por exemplo).
+    fn move_to(&mut self, new_head: GridPos) {
+        self.last_moved = self.direction;
         self.body.push_front(new_head);
         self.tail = self.body.pop_back();
     }
 
-    fn head_position(&self) -> (i32, i32) {
-        let head = self.body.front().unwrap();
-        (head.x, head.y)
+    fn occupies(&self, pos: GridPos) -> bool {
+        self.body.contains(&pos)
     }
 
     fn is_overlapping_tail(&self) -> bool {
-        let (hx, hy) = self.head_position();
-        for block in self.body.iter().skip(1) {
-            if block.x == hx && block.y == hy {
-                return true;
-            }
-        }
-        false
+        let head = self.head();
+        self.body.iter().skip(1).any(|&b| b == head)
+    }
+}
+
+/// Médias móveis, em segundos, do tempo gasto em cada parte do quadro.
+#[derive(Default)]
+struct FrameTimes {
+    update: f32,
+    draw: f32,
+    last_update: f32,
+    /// O último quadro estourou `FRAME_BUDGET`.
+    over_budget: bool,
+}
+
+impl FrameTimes {
+    fn record_update(&mut self, secs: f32) {
+        self.last_update = secs;
+        self.update += (secs - self.update) * FRAME_TIME_SMOOTHING;
+    }
+
+    fn record_draw(&mut self, secs: f32) {
+        self.draw += (secs - self.draw) * FRAME_TIME_SMOOTHING;
+        self.over_budget = self.last_update + secs > FRAME_BUDGET;
     }
 }
 
@@ -98,324 +353,3194 @@ impl Snake {
 struct GameState {
     mode: GameMode,
     snake: Snake,
-    food_x: i32,
-    food_y: i32,
+    food: GridPos,
     score: u32,
     time_since_last_update: f32,
     grid_width: i32,
     grid_height: i32,
     // CORRIGIDO: A fonte não é mais guardada no estado. Ela é registrada no contexto gráfico.
+    config: Config,
+    leaderboard: Leaderboard,
+    // Na tela do placar, o pedido de zerá-lo espera a confirmação.
+    confirm_reset: bool,
+    stats: Stats,
+    menu_selection: usize,
+    settings_selection: usize,
+    // Últimas posições da cabeça, da mais recente para a mais antiga. As
+    // primeiras ficam sob o corpo; o rastro é o que vem depois da cauda.
+    trail: VecDeque<GridPos>,
+    // Lado para o qual a próxima largada sai virada, quando a partida começou
+    // por uma tecla de direção no menu. Vale só para essa largada.
+    next_heading: Option<Direction>,
+    slow_motion: bool,
+    // Células internas que se comportam como parede.
+    obstacles: Vec<GridPos>,
+    // Modo rastro vira parede: células que a cauda deixou, com os segundos que
+    // faltam para endurecerem, e as que já viraram obstáculo.
+    pending_trail_walls: VecDeque<(GridPos, f32)>,
+    trail_walls: HashSet<GridPos>,
+    ring_shrink_timer: f32,
+    ring_inset: i32,
+    special_food: Option<SpecialFood>,
+    // Sobreposição de depuração (F3), que também libera as teclas de teste.
+    debug: bool,
+    // Buscas de caminho do último passo dos inimigos, desenhadas com a depuração ligada.
+    ai_searches: Vec<ai::Search>,
+    // Gravação de quadros em PNG ligada com F9 na depuração.
+    recording: Option<Recording>,
+    // Eventos emitidos desde o último quadro; esvaziados em `process_events`.
+    events: Vec<GameEvent>,
+    last_event: Option<GameEvent>,
+    food_move_timer: f32,
+    // Cobras controladas pelo computador que disputam a comida.
+    enemies: Vec<Snake>,
+    // Todo sorteio do jogo passa por aqui; com a mesma semente, as mesmas
+    // entradas produzem sempre a mesma partida.
+    rng: StdRng,
+    seed: u64,
+    // Viradas pedidas pelo jogador que ainda não foram aplicadas, uma por passo.
+    input_queue: VecDeque<Direction>,
+    music: Music,
+    sounds: Sounds,
+    death_cause: Option<DeathCause>,
+    // Segundos restantes da animação de entrada; a cobra só anda quando zera.
+    intro_timer: f32,
+    // Parte visível do mundo, em unidades de BLOCK_SIZE. Só difere da tela
+    // inteira quando a arena é maior que a janela.
+    camera: Rect,
+    speed_pads: Vec<SpeedPad>,
+    // Efeito do último piso pisado e quantos passos ele ainda dura.
+    speed_effect: Option<(PadKind, u32)>,
+    // Segundos jogados na partida atual e a pontuação a cada mudança, para o
+    // gráfico da tela de fim de jogo.
+    run_time: f32,
+    score_history: Vec<(f32, u32)>,
+    // Últimas teclas digitadas no menu, para reconhecer códigos.
+    recent_keys: VecDeque<KeyCode>,
+    // Código aceito mais recentemente e por quanto tempo a confirmação aparece.
+    cheat_flash: Option<(Cheat, f32)>,
+    // Liberada por código; vale até fechar o jogo e marca as partidas no placar.
+    top_speed: bool,
+    // Corpos da cobra nos últimos passos, do mais antigo para o mais recente.
+    replay_frames: VecDeque<LinkedList<GridPos>>,
+    replay_timer: f32,
+    themes: Vec<Theme>,
+    // Modo passo a passo da depuração: a partida fica parada e só avança
+    // `pending_steps` passos. Sobrevive ao reinício, então a próxima partida
+    // já começa parada.
+    step_mode: bool,
+    pending_steps: u32,
+    // Segundos desde o último passo pedido no modo passo a passo.
+    step_idle_timer: f32,
+    // Dia do desafio diário em andamento; `None` numa partida comum.
+    daily: Option<u64>,
+    particles: Particles,
+    // A cobra explodiu e não deve mais ser desenhada nesta partida.
+    exploded: bool,
+    // Posições roteirizadas que ainda faltam para a comida comum.
+    food_script: VecDeque<GridPos>,
+    // Segundos que a tecla de investida está segurada; `None` se está solta.
+    dash_charge: Option<f32>,
+    // A investida foi solta carregada e acontece no próximo passo.
+    dash_pending: bool,
+    dash_cooldown: f32,
+    // Segundos desde que a tela de fim de jogo apareceu.
+    game_over_timer: f32,
+    // Tempo restante da animação do segmento que acabou de crescer no fim da cauda.
+    growth_timer: f32,
+    frame_times: FrameTimes,
+    // Malha do corpo no estilo contínuo e o corpo a partir do qual foi montada.
+    body_mesh: Option<(LinkedList<GridPos>, Mesh)>,
+    // Malha branca da maçã no formato do tema, pintada na hora de desenhar.
+    food_mesh: Option<(FoodShape, Mesh)>,
+    console: Console,
+    // Intervalo fixado pelo console; vale só até o fim da partida.
+    speed_override: Option<f32>,
+    // A tela já foi tocada nesta execução, então o direcional aparece sozinho.
+    touch_detected: bool,
+    // Código sendo digitado na tela de importar desafio, e se o último era inválido.
+    challenge_input: String,
+    challenge_error: bool,
+    // Configuração do jogador, guardada enquanto as regras de um desafio importado valem.
+    config_backup: Option<Config>,
+    // Vidas que ainda restam nesta partida e o tempo de invencibilidade depois de gastar uma.
+    lives: u32,
+    invincible_timer: f32,
+    // Segundos que ainda restam das paredes seguras do começo da partida.
+    start_grace_timer: f32,
+    // Segundos que ainda restam com os controles invertidos pela maldição.
+    inverted_timer: f32,
+    // Tempo restante do aviso de volta para trás recusada.
+    reverse_warning_timer: f32,
+    // Segundos de partida que marcam a fase da língua; ela aparece no começo de cada `TONGUE_INTERVAL`.
+    tongue_timer: f32,
+    score_popups: Vec<ScorePopup>,
+    // Sequência de maçãs em linha: onde e quando foi comida a última e
+    // quantas vieram seguidas.
+    last_meal: Option<(GridPos, f32)>,
+    chain: u32,
+    // Quadros desde o último passo, no ritmo por quadros.
+    frame_counter: u32,
+    // Últimas células da comida móvel, da mais recente para a mais antiga.
+    food_trail: VecDeque<GridPos>,
+    magnet_timer: f32,
+    // As duas arenas da tela dividida, enquanto a corrida está na tela.
+    race: Option<[Board; 2]>,
+    race_timer: f32,
 }
 
 impl GameState {
-    fn new(ctx: &mut Context) -> GameResult<Self> {
-        let (screen_w, screen_h) = ctx.gfx.drawable_size();
-        let grid_width = (screen_w / BLOCK_SIZE) as i32;
-        let grid_height = (screen_h / BLOCK_SIZE) as i32;
+    fn new(ctx: &mut Context, config: Config) -> GameResult<Self> {
+        let (screen_w, screen_h) = ui_size(ctx, &config);
+        let (grid_width, grid_height) = config.grid_size(screen_w, screen_h, BLOCK_SIZE);
 
         let font_data = graphics::FontData::from_path(ctx, "/BungeeShade-Regular.ttf")?;
         ctx.gfx.add_font(MAIN_FONT, font_data);
 
+        let mut state = GameState::from_parts(
+            config,
+            Leaderboard::load(ctx),
+            grid_width,
+            grid_height,
+            rand::rng().random(),
+        );
+        state.music = Music::load(ctx);
+        state.sounds = Sounds::load(ctx);
+        state.stats = Stats::load(ctx);
+        state.themes = theme::load_all(ctx);
+        Ok(state)
+    }
+
+    /// Monta o estado sem depender da ggez, para que a simulação possa rodar
+    /// sem janela nem GPU.
+    fn from_parts(config: Config, leaderboard: Leaderboard, grid_width: i32, grid_height: i32, seed: u64) -> Self {
+        let particles = Particles::new(config.max_particles);
         let mut state = GameState {
             mode: GameMode::Menu,
-            snake: Snake::new(3, 2),
-            food_x: 0,
-            food_y: 0,
+            snake: Snake::new(SNAKE_START, Direction::Right),
+            food: GridPos::default(),
             score: 0,
             time_since_last_update: 0.0,
             grid_width,
             grid_height,
+            config,
+            leaderboard,
+            confirm_reset: false,
+            stats: Stats::default(),
+            menu_selection: 0,
+            settings_selection: 0,
+            trail: VecDeque::with_capacity(TRAIL_LENGTH),
+            next_heading: None,
+            slow_motion: false,
+            obstacles: Vec::new(),
+            pending_trail_walls: VecDeque::new(),
+            trail_walls: HashSet::new(),
+            ring_shrink_timer: 0.0,
+            ring_inset: 0,
+            special_food: None,
+            debug: false,
+            ai_searches: Vec::new(),
+            recording: None,
+            events: Vec::new(),
+            last_event: None,
+            food_move_timer: 0.0,
+            enemies: Vec::new(),
+            rng: StdRng::seed_from_u64(seed),
+            seed,
+            input_queue: VecDeque::with_capacity(MAX_QUEUED_TURNS),
+            music: Music::default(),
+            sounds: Sounds::default(),
+            death_cause: None,
+            intro_timer: 0.0,
+            camera: Rect::new(0.0, 0.0, 0.0, 0.0),
+            speed_pads: Vec::new(),
+            speed_effect: None,
+            run_time: 0.0,
+            score_history: Vec::new(),
+            recent_keys: VecDeque::with_capacity(cheats::max_code_length()),
+            cheat_flash: None,
+            top_speed: false,
+            replay_frames: VecDeque::with_capacity(REPLAY_FRAMES + 1),
+            replay_timer: 0.0,
+            themes: theme::built_in(),
+            step_mode: false,
+            pending_steps: 0,
+            step_idle_timer: 0.0,
+            daily: None,
+            particles,
+            exploded: false,
+            food_script: VecDeque::new(),
+            dash_charge: None,
+            dash_pending: false,
+            dash_cooldown: 0.0,
+            game_over_timer: 0.0,
+            growth_timer: 0.0,
+            frame_times: FrameTimes::default(),
+            body_mesh: None,
+            food_mesh: None,
+            console: Console::default(),
+            speed_override: None,
+            touch_detected: false,
+            challenge_input: String::new(),
+            challenge_error: false,
+            config_backup: None,
+            lives: 0,
+            invincible_timer: 0.0,
+            start_grace_timer: 0.0,
+            inverted_timer: 0.0,
+            reverse_warning_timer: 0.0,
+            tongue_timer: 0.0,
+            score_popups: Vec::new(),
+            last_meal: None,
+            chain: 0,
+            frame_counter: 0,
+            food_trail: VecDeque::new(),
+            race: None,
+            race_timer: 0.0,
+            magnet_timer: 0.0,
         };
         state.add_food();
-        Ok(state)
+        state
     }
 
-    fn add_food(&mut self) {
-        let mut rng = thread_rng();
-        if self.grid_width > 2 && self.grid_height > 2 {
-            let mut new_x = rng.gen_range(1..(self.grid_width - 1));
-            let mut new_y = rng.gen_range(1..(self.grid_height - 1));
-            while self.snake.body.iter().any(|b| b.x == new_x && b.y == new_y) {
-                new_x = rng.gen_range(1..(self.grid_width - 1));
-                new_y = rng.gen_range(1..(self.grid_height - 1));
+    /// Indica se a comida comum está nesta célula (nunca, com a comida desligada).
+    fn is_food_at(&self, pos: GridPos) -> bool {
+        self.config.food_enabled && self.food == pos
+    }
+
+    /// Indica se a célula não tem parede, cobra, comida nem piso de velocidade.
+    /// Como a comida só nasce em células livres, ela nunca cobre um piso.
+    fn is_free(&self, pos: GridPos) -> bool {
+        !(self.is_wall(pos)
+            || self.snake.occupies(pos)
+            || self.enemies.iter().any(|e| e.occupies(pos))
+            || self.is_food_at(pos)
+            || self.special_food.as_ref().is_some_and(|f| f.pos == pos)
+            || self.speed_pads.iter().any(|p| p.pos == pos))
+    }
+
+    /// Células da arena sem parede, cobra ou comida.
+    fn free_cells(&self) -> Vec<GridPos> {
+        let mut free_cells = Vec::new();
+        for y in 1..(self.grid_height - 1) {
+            for x in 1..(self.grid_width - 1) {
+                let pos = GridPos::new(x, y);
+                if self.is_free(pos) {
+                    free_cells.push(pos);
+                }
             }
-            self.food_x = new_x;
-            self.food_y = new_y;
         }
+        free_cells
     }
 
-    /// Reinicia o estado do jogo para começar uma nova partida.
-    fn restart(&mut self) {
-        self.snake = Snake::new(3, 2);
-        self.score = 0;
-        self.mode = GameMode::Playing;
-        self.add_food();
+    /// Células que a cabeça consegue alcançar sem atravessar paredes ou
+    /// obstáculos. Cobras não bloqueiam, porque saem do caminho com o tempo.
+    fn reachable_cells(&self) -> HashSet<GridPos> {
+        let start = self.snake.head();
+        let mut reachable = HashSet::from([start]);
+        let mut frontier = vec![start];
+        while let Some(pos) = frontier.pop() {
+            for dir in Direction::ALL {
+                let mut next = pos.neighbor(dir);
+                if self.config.wrap_edges && self.is_out_of_bounds(next) {
+                    next = self.wrap(next);
+                }
+                if !self.is_wall(next) && reachable.insert(next) {
+                    frontier.push(next);
+                }
+            }
+        }
+        reachable
     }
 
-    /// Redefine o estado do jogo e volta para a tela de menu.
-    fn reset_to_menu(&mut self) {
-        self.snake = Snake::new(3, 2);
-        self.score = 0;
-        self.mode = GameMode::Menu;
-        self.add_food();
+    /// Puxa a comida uma célula em direção à cabeça, se houver vizinha livre
+    /// mais perto dela.
+    fn pull_food(&mut self) {
+        let head = self.snake.head();
+        let closer = Direction::ALL
+            .into_iter()
+            .map(|dir| self.food.neighbor(dir))
+            .filter(|&pos| self.is_free(pos) && pos.manhattan(head) < self.food.manhattan(head))
+            .min_by_key(|pos| pos.manhattan(head));
+        if let Some(pos) = closer {
+            self.food = pos;
+        }
     }
-}
 
-impl EventHandler for GameState {
-    fn update(&mut self, ctx: &mut Context) -> GameResult {
-        if !matches!(self.mode, GameMode::Playing) {
-            return Ok(());
+    /// Move a comida para uma célula vizinha livre, se houver alguma.
+    fn step_food(&mut self) {
+        let neighbors: Vec<GridPos> = Direction::ALL
+            .into_iter()
+            .map(|dir| self.food.neighbor(dir))
+            .filter(|&pos| self.is_free(pos))
+            .collect();
+        if !neighbors.is_empty() {
+            self.food_trail.push_front(self.food);
+            self.food_trail.truncate(FOOD_TRAIL_LENGTH);
+            self.food = neighbors[self.rng.random_range(0..neighbors.len())];
         }
+    }
 
-        self.time_since_last_update += ctx.time.delta().as_secs_f32();
-        let update_interval = (0.15 - (self.score as f32 * 0.005)).max(0.05);
+    fn add_food(&mut self) {
+        // A comida nova nasce longe da antiga, então o rastro recomeça.
+        self.food_trail.clear();
+        if !self.config.food_enabled {
+            return;
+        }
+        // Comida em bolsões fechados do labirinto deixaria a partida impossível.
+        let reachable = self.reachable_cells();
 
-        if self.time_since_last_update > update_interval {
-            self.snake.move_forward();
-            self.time_since_last_update = 0.0;
+        // Posições do roteiro que caem em parede, cobra ou bolsão são descartadas.
+        while let Some(pos) = self.food_script.pop_front() {
+            if self.is_free(pos) && reachable.contains(&pos) {
+                self.food = pos;
+                return;
+            }
+            eprintln!("Posição roteirizada da comida ({}, {}) ocupada; ignorada.", pos.x, pos.y);
+        }
 
-            let (head_x, head_y) = self.snake.head_position();
-            if head_x == self.food_x && head_y == self.food_y {
-                if let Some(tail) = self.snake.tail.take() {
-                    self.snake.body.push_back(tail);
+        if !self.free_cells().iter().any(|p| reachable.contains(p)) {
+            if matches!(self.mode, GameMode::Playing) {
+                self.game_over(DeathCause::Stalemate);
+            }
+            return;
+        }
+
+        // O sorteio é feito sobre o interior inteiro e repetido até cair numa
+        // célula livre, como em `Board::place_food`. Quantos sorteios isso gasta
+        // depende das células ocupadas, então a comida só se repete entre duas
+        // partidas com a mesma semente, as mesmas regras, a mesma grade e os
+        // mesmos movimentos, que é o que o desafio diário garante.
+        // O peso de cada célula cresce com a distância até a parede mais próxima;
+        // com viés 0 todos os pesos valem 1 e o sorteio é uniforme.
+        let bias = self.config.food_center_bias;
+        let cells: Vec<GridPos> = (1..self.grid_height - 1)
+            .flat_map(|y| (1..self.grid_width - 1).map(move |x| GridPos::new(x, y)))
+            .collect();
+        let weights: Vec<f32> = cells
+            .iter()
+            .map(|b| {
+                let distance = b.x.min(b.y).min(self.grid_width - 1 - b.x).min(self.grid_height - 1 - b.y);
+                (distance as f32).powf(bias)
+            })
+            .collect();
+        let total = weights.iter().sum::<f32>();
+
+        loop {
+            let mut pick = self.rng.random_range(0.0..total);
+            let mut chosen = cells.len() - 1;
+            for (i, weight) in weights.iter().enumerate() {
+                if pick < *weight {
+                    chosen = i;
+                    break;
                 }
-                self.score += 1;
-                self.add_food();
+                pick -= weight;
             }
+            let pos = cells[chosen];
+            if self.is_free(pos) && reachable.contains(&pos) {
+                self.food = pos;
+                return;
+            }
+        }
+    }
 
-            if head_x <= 0
-                || head_x >= self.grid_width - 1
-                || head_y <= 0
-                || head_y >= self.grid_height - 1
-                || self.snake.is_overlapping_tail()
-            {
-                self.mode = GameMode::GameOver;
+    /// Uma célula livre qualquer, sorteada.
+    fn random_free_cell(&mut self) -> Option<GridPos> {
+        let free_cells = self.free_cells();
+        if free_cells.is_empty() {
+            return None;
+        }
+        Some(free_cells[self.rng.random_range(0..free_cells.len())])
+    }
+
+    /// Coloca uma maçã especial numa célula livre qualquer.
+    fn spawn_special_food(&mut self, kind: SpecialFoodKind) {
+        if let Some(pos) = self.random_free_cell() {
+            self.special_food = Some(SpecialFood {
+                kind,
+                pos,
+                ttl: SPECIAL_FOOD_LIFETIME,
+            });
+        }
+    }
+
+    /// Coloca um obstáculo numa célula livre qualquer.
+    fn spawn_obstacle(&mut self) {
+        if let Some(pos) = self.random_free_cell() {
+            self.obstacles.push(pos);
+        }
+    }
+
+    /// Espalha obstáculos por `maze_density` das células internas, deixando
+    /// livre a área em volta da largada. Se a primeira maçã ficar fora de
+    /// alcance, sorteia de novo; depois de `MAZE_ATTEMPTS` tentativas a
+    /// partida começa sem labirinto.
+    fn scatter_obstacles(&mut self) {
+        let interior = ((self.grid_width - 2) * (self.grid_height - 2)).max(0) as f32;
+        let count = (interior * self.config.maze_density).round() as usize;
+        let start = self.snake.head();
+        for _ in 0..MAZE_ATTEMPTS {
+            self.obstacles.clear();
+            let mut candidates: Vec<GridPos> =
+                self.free_cells().into_iter().filter(|p| p.manhattan(start) > MAZE_CLEARANCE).collect();
+            candidates.shuffle(&mut self.rng);
+            candidates.truncate(count);
+            self.obstacles = candidates;
+            if !self.config.food_enabled || self.reachable_cells().contains(&self.food) {
+                return;
             }
         }
-        Ok(())
+        self.obstacles.clear();
+        eprintln!("Nenhum labirinto com a comida alcançável; a partida começa sem obstáculos.");
     }
 
-    fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        let mut canvas = graphics::Canvas::from_frame(ctx, Color::from([0.15, 0.17, 0.18, 1.0]));
-        draw_background(self, ctx, &mut canvas)?;
+    /// Coloca um piso de velocidade numa célula livre qualquer.
+    fn spawn_speed_pad(&mut self, kind: PadKind) {
+        if let Some(pos) = self.random_free_cell() {
+            self.speed_pads.push(SpeedPad { kind, pos });
+        }
+    }
 
-        match self.mode {
-            GameMode::Menu => {
-                draw_menu(self, ctx, &mut canvas)?;
+    /// Cria um inimigo numa faixa livre de três células, longe da cabeça do jogador.
+    fn spawn_enemy(&mut self) {
+        let head = self.snake.head();
+        let candidates: Vec<GridPos> = self
+            .free_cells()
+            .into_iter()
+            .filter(|&b| self.is_free(b.neighbor(Direction::Left)) && self.is_free(GridPos::new(b.x - 2, b.y)))
+            .filter(|&b| b.manhattan(head) >= ENEMY_SPAWN_DISTANCE)
+            .collect();
+        if candidates.is_empty() {
+            return;
+        }
+        let cell = candidates[self.rng.random_range(0..candidates.len())];
+        self.enemies.push(Snake::new(cell, Direction::Right));
+    }
+
+    /// Move cada inimigo um passo em direção à comida. Inimigos que batem
+    /// morrem e renascem em outro lugar.
+    fn step_enemies(&mut self) {
+        let mut respawns = 0;
+        let mut searches = Vec::new();
+        for i in 0..self.enemies.len() {
+            let head = self.enemies[i].head();
+            let blocked = |pos: GridPos| {
+                self.is_wall(pos) || self.snake.occupies(pos) || self.enemies.iter().any(|e| e.occupies(pos))
+            };
+            // Sem comida na arena, os inimigos apenas vagueiam.
+            let chase = self.config.food_enabled.then(|| {
+                ai::search(head, self.food, self.grid_width, self.grid_height, blocked)
+            });
+            let direction = chase
+                .as_ref()
+                .and_then(|search| search.first_direction(head))
+                .or_else(|| ai::safe_direction(head, self.enemies[i].direction, blocked));
+            if self.debug {
+                searches.extend(chase);
             }
-            GameMode::Playing => {
-                draw_gameplay(self, ctx, &mut canvas)?;
+
+            let enemy = &mut self.enemies[i];
+            if let Some(direction) = direction {
+                enemy.direction = direction;
             }
-            GameMode::GameOver => {
-                draw_gameplay(self, ctx, &mut canvas)?;
-                draw_game_over(self, ctx, &mut canvas)?;
+            enemy.move_forward();
+
+            if self.is_food_at(self.enemies[i].head()) {
+                let enemy = &mut self.enemies[i];
+                if let Some(tail) = enemy.tail.take() {
+                    enemy.body.push_back(tail);
+                }
+                self.add_food();
             }
         }
 
-        canvas.finish(ctx)?;
-        Ok(())
+        // Os inimigos só morrem depois que todos se moveram, para que a ordem
+        // de atualização não favoreça nenhum deles.
+        let mut i = 0;
+        while i < self.enemies.len() {
+            let head = self.enemies[i].head();
+            let crashed = self.is_wall(head)
+                || self.enemies[i].is_overlapping_tail()
+                || self.snake.occupies(head)
+                || self.enemies.iter().enumerate().any(|(j, e)| j != i && e.occupies(head));
+            if crashed {
+                self.enemies.remove(i);
+                respawns += 1;
+            } else {
+                i += 1;
+            }
+        }
+        for _ in 0..respawns {
+            self.spawn_enemy();
+        }
+        self.ai_searches = searches;
     }
 
-    fn resize_event(&mut self, _ctx: &mut Context, width: f32, height: f32) -> GameResult {
-        self.grid_width = (width / BLOCK_SIZE) as i32;
-        self.grid_height = (height / BLOCK_SIZE) as i32;
-        self.reset_to_menu();
-        Ok(())
+    /// Limpa tudo o que pertence a uma partida: cobra, rastro, obstáculos e comida.
+    fn reset_run(&mut self) {
+        // Virada para a esquerda, a cobra larga espelhada, do outro lado da arena.
+        self.snake = match self.next_heading.take() {
+            Some(Direction::Left) => {
+                Snake::new(GridPos::new(self.grid_width - 1 - SNAKE_START.x, SNAKE_START.y), Direction::Left)
+            }
+            _ => Snake::new(SNAKE_START, Direction::Right),
+        };
+        self.input_queue.clear();
+        self.trail.clear();
+        self.obstacles.clear();
+        self.pending_trail_walls.clear();
+        self.trail_walls.clear();
+        self.special_food = None;
+        self.food_move_timer = 0.0;
+        self.ring_shrink_timer = 0.0;
+        self.ring_inset = 0;
+        self.enemies.clear();
+        self.ai_searches.clear();
+        self.speed_pads.clear();
+        self.speed_effect = None;
+        self.replay_frames.clear();
+        self.particles.clear();
+        self.particles.capacity = self.config.max_particles;
+        self.exploded = false;
+        self.dash_charge = None;
+        self.dash_pending = false;
+        self.dash_cooldown = 0.0;
+        self.growth_timer = 0.0;
+        self.tongue_timer = 0.0;
+        self.score_popups.clear();
+        self.last_meal = None;
+        self.chain = 0;
+        self.speed_override = None;
+        self.lives = self.config.wall_lives;
+        self.invincible_timer = 0.0;
+        self.start_grace_timer = self.config.start_grace;
+        self.inverted_timer = 0.0;
+        self.reverse_warning_timer = 0.0;
+        self.frame_counter = 0;
+        self.magnet_timer = 0.0;
+        self.time_since_last_update = 0.0;
+        self.add_food();
     }
 
-    fn key_down_event(&mut self, _ctx: &mut Context, input: KeyInput, _repeat: bool) -> GameResult {
-        if let Some(keycode) = input.keycode {
-            match self.mode {
-                GameMode::Menu => {
-                    if keycode == KeyCode::Return {
-                        self.restart();
-                    }
-                }
-                GameMode::Playing => {
-                    let dir = match keycode {
-                        KeyCode::Up     |   KeyCode::W  => Some(Direction::Up),
-                        KeyCode::Down   |   KeyCode::S  => Some(Direction::Down),
-                        KeyCode::Left   |   KeyCode::A  => Some(Direction::Left),
-                        KeyCode::Right  |   KeyCode::D  => Some(Direction::Right),
-                        _ => None,
-                    };
+    /// Começa uma partida com a cobra já virada para `heading`, que precisa
+    /// ser um dos lados.
+    fn restart_facing(&mut self, heading: Direction) {
+        self.next_heading = Some(heading);
+        self.restart();
+    }
 
-                    if let Some(d) = dir {
-                        if d != self.snake.direction.opposite() {
-                            self.snake.direction = d;
-                        }
-                    }
-                }
-                GameMode::GameOver => {
-                    self.reset_to_menu();
-                }
-            }
+    /// Reinicia o estado do jogo para começar uma nova partida.
+    fn restart(&mut self) {
+        // Cada partida recebe a própria semente, derivada da anterior, para que
+        // possa ser reproduzida sem depender do que aconteceu no menu.
+        let seed = self.rng.random();
+        self.start_run(seed, None);
+    }
+
+    /// Joga o desafio de `day`: a mesma semente, as regras padrão e a mesma
+    /// grade para todos, seja qual for a configuração ou a janela.
+    fn play_daily(&mut self, ctx: &Context, day: u64) {
+        self.play_challenge(ctx, daily::challenge(day));
+        self.daily = Some(day);
+    }
+
+    /// Começa uma corrida na tela dividida. As duas arenas recebem a mesma
+    /// semente, para que a comida apareça nos mesmos lugares.
+    fn start_race(&mut self, ctx: &Context) {
+        let (screen_w, screen_h) = ui_size(ctx, &self.config);
+        let (width, height) = ((screen_w / 2.0 / BLOCK_SIZE) as i32, (screen_h / BLOCK_SIZE) as i32);
+        let seed = self.rng.random();
+        self.race = Some([Board::new(width, height, seed), Board::new(width, height, seed)]);
+        self.race_timer = 0.0;
+        self.mode = GameMode::SplitScreen;
+    }
+
+    /// Joga um desafio importado: as regras e a grade dele valem até voltar
+    /// ao menu.
+    fn play_challenge(&mut self, ctx: &Context, challenge: Challenge) {
+        if self.config_backup.is_none() {
+            self.config_backup = Some(self.config.clone());
         }
-        Ok(())
+        challenge.apply(&mut self.config);
+        self.resize_grid(ctx);
+        self.start_run(challenge.seed, None);
     }
-}
 
-// --- FUNÇÕES DE DESENHO AUXILIARES ---
+    fn start_run(&mut self, seed: u64, daily: Option<u64>) {
+        self.seed = seed;
+        self.daily = daily;
+        self.rng = StdRng::seed_from_u64(self.seed);
+        self.food_script = self.config.food_script.iter().copied().collect();
+        self.reset_run();
+        if self.config.maze_density > 0.0 {
+            self.scatter_obstacles();
+        }
+        for _ in 0..self.config.enemy_count {
+            self.spawn_enemy();
+        }
+        if self.config.speed_pads {
+            for _ in 0..SPEED_PADS_PER_KIND {
+                self.spawn_speed_pad(PadKind::Fast);
+                self.spawn_speed_pad(PadKind::Slow);
+            }
+        }
+        self.score = self.config.starting_score;
+        self.run_time = 0.0;
+        self.score_history = vec![(0.0, self.score)];
+        self.intro_timer = INTRO_DURATION;
+        self.mode = GameMode::Playing;
+    }
 
-fn draw_background(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
-    let wall_mesh = Mesh::new_rectangle(
-        ctx,
-        DrawMode::fill(),
-        Rect::new(0.0, 0.0, BLOCK_SIZE, BLOCK_SIZE),
-        Color::from([0.4, 0.4, 0.4, 1.0]),
-    )?;
-    let grid_mesh = Mesh::new_rectangle(
-        ctx,
-        DrawMode::stroke(0.5),
-        Rect::new(0.0, 0.0, BLOCK_SIZE, BLOCK_SIZE),
-        Color::from([0.2, 0.22, 0.23, 1.0]),
-    )?;
+    /// Redefine o estado do jogo e volta para a tela de menu, com as
+    /// configurações e a grade de antes de um desafio.
+    fn reset_to_menu(&mut self, ctx: &Context) {
+        if let Some(config) = self.config_backup.take() {
+            self.config = config;
+            self.resize_grid(ctx);
+        }
+        self.show_menu();
+    }
 
-    for y in 0..gs.grid_height {
-        for x in 0..gs.grid_width {
-            if x == 0 || x == gs.grid_width - 1 || y == 0 || y == gs.grid_height - 1 {
-                canvas.draw(
-                    &wall_mesh,
-                    Vec2::new(x as f32 * BLOCK_SIZE, y as f32 * BLOCK_SIZE),
-                );
-            } else {
-                canvas.draw(
-                    &grid_mesh,
-                    Vec2::new(x as f32 * BLOCK_SIZE, y as f32 * BLOCK_SIZE),
-                );
+    /// A parte de `reset_to_menu` que não depende da janela.
+    fn show_menu(&mut self) {
+        self.race = None;
+        self.food_script.clear();
+        let finished = (self.snake.body.clone(), self.snake.direction);
+        self.reset_run();
+        // Com a prévia ligada, quem anda atrás do menu é a cobra da partida
+        // que acabou, e não uma nova.
+        if self.config.menu_preview {
+            (self.snake.body, self.snake.direction) = finished;
+            self.snake.last_moved = self.snake.direction;
+            self.snake.tail = None;
+            if self.snake.occupies(self.food) {
+                self.add_food();
             }
         }
+        self.score = 0;
+        self.mode = GameMode::Menu;
     }
-    Ok(())
-}
 
-fn draw_gameplay(gs: &mut GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
-    let head_color = Color::from([0.9, 0.5, 0.2, 1.0]);
-    let body_color = Color::from([0.8, 0.4, 0.1, 1.0]);
+    /// Indica se a célula é letal: a borda da arena ou um obstáculo.
+    fn is_wall(&self, pos: GridPos) -> bool {
+        self.is_out_of_bounds(pos) || self.obstacles.contains(&pos)
+    }
 
-    let block_mesh = Mesh::new_rectangle(
-        ctx,
-        DrawMode::fill(),
-        Rect::new(2.0, 2.0, BLOCK_SIZE - 4.0, BLOCK_SIZE - 4.0),
-        Color::WHITE,
-    )?;
-    let eye_mesh = Mesh::new_rectangle(
-        ctx,
-        DrawMode::fill(),
-        Rect::new(0.0, 0.0, 4.0, 4.0),
-        Color::BLACK,
-    )?;
+    /// Indica se a célula está além do limite letal da arena. A faixa da borda,
+    /// desenhada em `draw_background`, conta como parede, a menos que seja
+    /// uma zona de aviso; então só as células fora da grade matam.
+    fn is_out_of_bounds(&self, pos: GridPos) -> bool {
+        let margin = if self.config.passable_border { 0 } else { 1 };
+        pos.x < margin || pos.y < margin || pos.x >= self.grid_width - margin || pos.y >= self.grid_height - margin
+    }
 
-    for (i, block) in gs.snake.body.iter().enumerate() {
-        let color = if i == 0 { head_color } else { body_color };
-        let pos = Vec2::new(block.x as f32 * BLOCK_SIZE, block.y as f32 * BLOCK_SIZE);
-        canvas.draw(&block_mesh, DrawParam::new().dest(pos).color(color));
-
-        if i == 0 {
-            let (eye1_offset, eye2_offset) = match gs.snake.direction {
-                Direction::Up => (Vec2::new(4.0, 4.0), Vec2::new(BLOCK_SIZE - 8.0, 4.0)),
-                Direction::Down => {
-                    (
-                        Vec2::new(4.0, BLOCK_SIZE - 8.0),
-                        Vec2::new(BLOCK_SIZE - 8.0, BLOCK_SIZE - 8.0),
-                    )
-                }
-                Direction::Left => {
-                    (
-                        Vec2::new(4.0, 4.0),
-                        Vec2::new(4.0, BLOCK_SIZE - 8.0),
-                    )
-                }
-                Direction::Right => {
-                    (
-                        Vec2::new(BLOCK_SIZE - 8.0, 4.0),
-                        Vec2::new(BLOCK_SIZE - 8.0, BLOCK_SIZE - 8.0),
-                    )
+    /// Leva uma posição além da borda para o lado oposto da área jogável.
+    /// O resultado pode ser um obstáculo, que continua letal.
+    fn wrap(&self, pos: GridPos) -> GridPos {
+        let margin = if self.config.passable_border { 0 } else { 1 };
+        let inner = GridPos::new(pos.x - margin, pos.y - margin)
+            .wrapped(self.grid_width - 2 * margin, self.grid_height - 2 * margin);
+        GridPos::new(inner.x + margin, inner.y + margin)
+    }
+
+    /// Transforma o anel mais externo da área livre em parede.
+    /// Retorna `false` quando a arena já está no tamanho mínimo.
+    fn shrink_arena(&mut self) -> bool {
+        let inset = self.ring_inset + 1;
+        let (left, top) = (inset, inset);
+        let (right, bottom) = (self.grid_width - 1 - inset, self.grid_height - 1 - inset);
+        // Tamanho da área que sobra por dentro do novo anel.
+        if right - left - 1 < MIN_ARENA_SIZE || bottom - top - 1 < MIN_ARENA_SIZE {
+            return false;
+        }
+
+        for y in top..=bottom {
+            for x in left..=right {
+                if x == left || x == right || y == top || y == bottom {
+                    self.obstacles.push(GridPos::new(x, y));
                 }
-            };
-            canvas.draw(&eye_mesh, pos + eye1_offset);
-            canvas.draw(&eye_mesh, pos + eye2_offset);
+            }
         }
+        self.ring_inset = inset;
+        // A maçã especial e os pisos não mudam de lugar; os que o anel cobriu somem.
+        if self.special_food.as_ref().is_some_and(|f| self.is_wall(f.pos)) {
+            self.special_food = None;
+        }
+        let obstacles = &self.obstacles;
+        self.speed_pads.retain(|p| !obstacles.contains(&p.pos));
+        true
     }
 
-    canvas.draw(
-        &block_mesh,
-        DrawParam::new()
-            .dest(Vec2::new(
-                gs.food_x as f32 * BLOCK_SIZE,
-                gs.food_y as f32 * BLOCK_SIZE,
-            ))
-            .color(Color::RED),
-    );
+    /// Intervalo, em segundos, entre dois movimentos da cobra.
+    fn update_interval(&self) -> f32 {
+        if let Some(secs) = self.speed_override {
+            return secs;
+        }
+        if self.top_speed {
+            return MIN_INTERVAL;
+        }
+        let interval =
+            (BASE_INTERVAL + self.config.starting_speed_offset - (self.score as f32 * 0.005)).max(MIN_INTERVAL);
+        match self.speed_effect {
+            Some((kind, _)) => interval * kind.interval_factor(),
+            None => interval,
+        }
+    }
 
-    let apple_mesh = Mesh::new_rectangle(
-        ctx,
-        DrawMode::fill(),
-        Rect::new(0.0, 0.0, BLOCK_SIZE * 0.5, BLOCK_SIZE * 0.5),
-        Color::RED,
-    )?;
-    canvas.draw(&apple_mesh, Vec2::new(10.0, 10.0));
+    /// Registra a partida no placar e mostra a tela de fim de jogo.
+    fn game_over(&mut self, cause: DeathCause) {
+        // Só a primeira morte de um passo conta; o impasse da comida pode vir
+        // antes de uma colisão no mesmo movimento.
+        if matches!(self.mode, GameMode::DeathReplay) {
+            return;
+        }
+        // A pose da morte entra por último, para ser o primeiro quadro rebobinado.
+        self.replay_frames.push_back(self.snake.body.clone());
+        self.replay_timer = 0.0;
+        self.mode = GameMode::DeathReplay;
+        self.death_cause = Some(cause);
+        self.events.push(GameEvent::Died { score: self.score, cause });
+    }
 
-    let mut score_text = Text::new(format!(": {}", gs.score));
-    // CORRIGIDO: Usa o nome registrado da fonte.
-    score_text.set_font(MAIN_FONT).set_scale(20.0);
+    /// Indica se algum código secreto que muda a partida está ligado: a
+    /// velocidade máxima ou a depuração, que libera o console e as teclas de teste.
+    fn cheating(&self) -> bool {
+        self.top_speed || self.debug
+    }
 
-    if let Some(text_rect) = score_text.dimensions(ctx) {
-        let h = text_rect.h;
-        canvas.draw(
-            &score_text,
-            DrawParam::new()
-                .dest(Vec2::new(10.0 + BLOCK_SIZE * 0.5, 12.0 - h * 0.5))
-                .color(Color::WHITE),
-        );
+    fn play_cue(&mut self, ctx: &Context, cue: Cue) {
+        if let Err(e) = self.sounds.play(ctx, cue, self.config.music_volume()) {
+            eprintln!("Não foi possível tocar o aviso sonoro: {}", e);
+        }
     }
 
-    Ok(())
-}
+    /// Reage aos eventos emitidos pela lógica do jogo desde o último quadro.
+    fn process_events(&mut self, ctx: &Context) {
+        for event in std::mem::take(&mut self.events) {
+            match event {
+                GameEvent::FoodEaten { .. } | GameEvent::SpecialFoodEaten { .. } => self.stats.food_eaten += 1,
+                GameEvent::Turned { .. } if self.config.turn_sound => self.play_cue(ctx, Cue::Turn),
+                GameEvent::NearWall if self.config.wall_sound => self.play_cue(ctx, Cue::Wall),
+                GameEvent::Turned { .. } | GameEvent::NearWall => {}
+                GameEvent::Died { score, cause } => {
+                    // As partidas do desafio diário têm um placar à parte, e
+                    // nele não entram as jogadas com trapaças ligadas.
+                    match self.daily {
+                        Some(day) if !self.cheating() => self.leaderboard.record_daily(day, score),
+                        Some(_) => {}
+                        None => self.leaderboard.record(Entry {
+                            score,
+                            handicapped: self.config.is_handicapped() || self.top_speed,
+                        }),
+                    }
+                    if let Err(e) = self.leaderboard.save(ctx) {
+                        eprintln!("Não foi possível salvar o placar: {}", e);
+                    }
+                    // A comida é contada evento a evento, mas o arquivo só é
+                    // gravado uma vez por partida.
+                    self.stats.record_run(score, cause, self.run_time);
+                    if let Err(e) = self.stats.save(ctx) {
+                        eprintln!("Não foi possível salvar as estatísticas: {}", e);
+                    }
+                }
+            }
+            self.last_event = Some(event);
+        }
+    }
 
-fn draw_centered_text(
-    canvas: &mut graphics::Canvas,
-    ctx: &mut Context,
-    text_str: &str,
-    size: f32,
-    y_offset: f32,
-    color: Color,
-) -> GameResult {
-    let (screen_w, screen_h) = ctx.gfx.drawable_size();
-    let mut text = Text::new(text_str);
-    // CORRIGIDO: Usa o nome registrado da fonte.
-    text.set_font(MAIN_FONT).set_scale(size);
+    /// Tema escolhido nas configurações, ou o padrão se ele sumiu.
+    fn theme(&self) -> &Theme {
+        self.themes.iter().find(|t| t.name == self.config.theme).unwrap_or(&self.themes[0])
+    }
 
-    if let Some(text_rect) = text.dimensions(ctx) {
-        let text_w = text_rect.w;
-        let pos = Vec2::new((screen_w - text_w) / 2.0, screen_h / 2.0 + y_offset);
-        canvas.draw(&text, DrawParam::new().dest(pos).color(color));
+    /// Guarda a tecla digitada no menu e aplica o código que ela completar.
+    /// Retorna se a tecla completou um código ou continua um que está sendo digitado.
+    fn track_cheat_key(&mut self, keycode: KeyCode) -> bool {
+        if self.recent_keys.len() == cheats::max_code_length() {
+            self.recent_keys.pop_front();
+        }
+        self.recent_keys.push_back(keycode);
+
+        if let Some(cheat) = cheats::find(&self.recent_keys) {
+            match cheat {
+                Cheat::Debug => self.debug = true,
+                Cheat::TopSpeed => self.top_speed = true,
+            }
+            self.recent_keys.clear();
+            self.cheat_flash = Some((cheat, CHEAT_FLASH_DURATION));
+            return true;
+        }
+        cheats::in_progress(&self.recent_keys)
     }
-    Ok(())
-}
 
-fn draw_menu(_gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
-    // CORRIGIDO: A fonte não é mais passada como argumento.
-    draw_centered_text(
-        canvas,
-        ctx,
-        "Rusted Snake",
-        64.0,
-        -100.0,
-        Color::from([0.8, 0.4, 0.1, 1.0]),
-    )?;
-    draw_centered_text(
-        canvas,
-        ctx,
-        "Pressione ENTER para começar",
-        24.0,
-        20.0,
+    /// Executa a ação da entrada selecionada no menu principal.
+    fn activate_menu_item(&mut self, ctx: &mut Context) {
+        match MENU_ITEMS[self.menu_selection] {
+            MenuItem::Play => self.restart(),
+            MenuItem::Daily => self.play_daily(ctx, daily::today()),
+            MenuItem::SplitScreen => self.start_race(ctx),
+            MenuItem::ImportChallenge => {
+                self.challenge_input.clear();
+                self.challenge_error = false;
+                self.mode = GameMode::ImportChallenge;
+            }
+            MenuItem::Settings => {
+                self.settings_selection = 0;
+                self.mode = GameMode::Settings;
+            }
+            MenuItem::Leaderboard => {
+                self.confirm_reset = false;
+                self.mode = GameMode::Leaderboard;
+            }
+            MenuItem::Stats => self.mode = GameMode::Stats,
+            MenuItem::Help => self.mode = GameMode::Help,
+            MenuItem::Quit => ctx.request_quit(),
+        }
+    }
+
+    /// Encerra o rebobinamento e mostra o fim de jogo, explodindo a cobra
+    /// antes, se a opção estiver ligada.
+    fn finish_death_replay(&mut self) {
+        if self.config.death_explosion {
+            let theme = self.theme().clone();
+            for (i, block) in self.snake.body.iter().enumerate() {
+                let color = if i == 0 { theme.snake_head } else { theme.snake_body };
+                let center = cell_to_pixel(*block) + Vec2::splat(BLOCK_SIZE * 0.5);
+                self.particles.burst(center, color, EXPLOSION_PARTICLES, EXPLOSION_SPEED, EXPLOSION_LIFETIME);
+            }
+            self.exploded = true;
+        }
+        self.game_over_timer = 0.0;
+        self.mode = GameMode::GameOver;
+    }
+
+    /// Células que a cabeça atravessa nos próximos passos se não virar, até
+    /// `projection_length` ou a primeira parede ou parte do corpo.
+    fn projected_path(&self) -> Vec<GridPos> {
+        let direction = self.input_queue.front().copied().unwrap_or(self.snake.direction);
+        let mut path = Vec::new();
+        let mut pos = self.snake.head();
+        for _ in 0..self.config.projection_length {
+            pos = pos.neighbor(direction);
+            if self.config.wrap_edges && self.is_out_of_bounds(pos) {
+                pos = self.wrap(pos);
+            }
+            if self.is_wall(pos) || self.snake.occupies(pos) {
+                break;
+            }
+            path.push(pos);
+        }
+        path
+    }
+
+    fn dpad_visible(&self) -> bool {
+        self.config.touch_dpad || self.touch_detected
+    }
+
+    /// Um toque ou clique no direcional vira uma virada, como a tecla equivalente.
+    fn press_dpad(&mut self, ctx: &Context, x: f32, y: f32) {
+        if !matches!(self.mode, GameMode::Playing) || !self.dpad_visible() {
+            return;
+        }
+        // O evento chega em pixels físicos; os botões estão em unidades da interface.
+        let scale = ui_scale(ctx, &self.config);
+        let point = [x / scale, y / scale];
+        if let Some(&(direction, _)) = dpad_buttons(self, ctx).iter().find(|(_, rect)| rect.contains(point)) {
+            self.queue_direction(self.steer(direction));
+        }
+    }
+
+    /// A direção em que uma tecla ou botão leva a cobra agora. Só troca o
+    /// rótulo: a proteção contra voltar para trás continua olhando a direção
+    /// de verdade, em `queue_direction`.
+    fn steer(&self, direction: Direction) -> Direction {
+        if self.inverted_timer > 0.0 { direction.opposite() } else { direction }
+    }
+
+    /// Executa a linha digitada no console e mostra a resposta.
+    fn run_console_command(&mut self) {
+        let line = self.console.take_input();
+        let reply = match console::parse(&line) {
+            Ok(command) => self.apply_command(command),
+            Err(e) => e,
+        };
+        self.console.print(reply);
+    }
+
+    fn apply_command(&mut self, command: Command) -> String {
+        match command {
+            Command::Speed(secs) => {
+                self.speed_override = secs;
+                secs.map_or("Velocidade normal".to_string(), |s| format!("Intervalo fixo em {:.3}s", s))
+            }
+            Command::Grow(count) => {
+                // Os segmentos novos empilham na ponta da cauda e se espalham
+                // conforme a cobra anda. O tamanho máximo vale aqui também.
+                let tail = *self.snake.body.back().expect("A cobra não tem corpo.");
+                let room = self.config.max_length.map_or(usize::MAX, |max| max.saturating_sub(self.snake.body.len()));
+                let count = (count as usize).min(room);
+                for _ in 0..count {
+                    self.snake.body.push_back(tail);
+                }
+                format!("A cobra cresceu {} segmentos", count)
+            }
+            Command::Theme(name) => match self.themes.iter().find(|t| t.name.to_lowercase() == name.to_lowercase()) {
+                Some(theme) => {
+                    self.config.theme = theme.name.clone();
+                    format!("Tema: {}", theme.name)
+                }
+                None => format!("Tema não encontrado: {}", name),
+            },
+            Command::Spawn(kind) => {
+                match kind {
+                    SpawnKind::Bonus => self.spawn_special_food(SpecialFoodKind::Bonus),
+                    SpawnKind::Poison => self.spawn_special_food(SpecialFoodKind::Poison),
+                    SpawnKind::Obstacle => self.spawn_obstacle(),
+                    SpawnKind::Enemy => self.spawn_enemy(),
+                }
+                "Feito".to_string()
+            }
+            Command::Help => console::HELP.to_string(),
+        }
+    }
+
+    /// Remonta a malha contínua do corpo, se ele mudou desde o último quadro.
+    fn refresh_body_mesh(&mut self, ctx: &Context) -> GameResult {
+        let body = self.replay_body().unwrap_or(&self.snake.body);
+        if self.body_mesh.as_ref().is_some_and(|(cached, _)| cached == body) {
+            return Ok(());
+        }
+        let mesh = body_path_mesh(ctx, body)?;
+        self.body_mesh = Some((body.clone(), mesh));
+        Ok(())
+    }
+
+    /// Remonta a malha da maçã, se o tema trocou de formato.
+    fn refresh_food_mesh(&mut self, ctx: &Context) -> GameResult {
+        let shape = self.theme().food_shape;
+        if self.food_mesh.as_ref().is_some_and(|(cached, _)| *cached == shape) {
+            return Ok(());
+        }
+        self.food_mesh = Some((shape, food_shape_mesh(ctx, shape)?));
+        Ok(())
+    }
+
+    /// Corpo da cobra a desenhar durante o rebobinamento, do fim para o começo.
+    fn replay_body(&self) -> Option<&LinkedList<GridPos>> {
+        if !matches!(self.mode, GameMode::DeathReplay) {
+            return None;
+        }
+        let progress = (self.replay_timer / REPLAY_DURATION).min(1.0);
+        let back = (progress * self.replay_frames.len() as f32) as usize;
+        self.replay_frames.iter().rev().nth(back.min(self.replay_frames.len() - 1))
+    }
+
+    /// Avança a simulação exatamente um passo: move as cobras e resolve
+    /// comida e colisões. Não depende de tempo real nem da ggez.
+    fn step(&mut self) {
+        if self.replay_frames.len() == REPLAY_FRAMES {
+            self.replay_frames.pop_front();
+        }
+        self.replay_frames.push_back(self.snake.body.clone());
+
+        if let Some(direction) = self.input_queue.pop_front() {
+            self.snake.direction = direction;
+            self.events.push(GameEvent::Turned { direction });
+        }
+
+        // Uma investida percorre duas células no mesmo passo, cada uma com as
+        // próprias colisões; parar no meio do caminho também mata.
+        let cells = if std::mem::take(&mut self.dash_pending) { 2 } else { 1 };
+        for i in 0..cells {
+            if !self.advance_snake() {
+                return;
+            }
+            let head = self.snake.head();
+            if i + 1 < cells && self.invincible_timer <= 0.0 && self.enemies.iter().any(|e| e.occupies(head)) {
+                self.game_over(DeathCause::Enemy);
+                return;
+            }
+        }
+
+        self.step_enemies();
+        let head = self.snake.head();
+        if self.invincible_timer <= 0.0 && self.enemies.iter().any(|e| e.occupies(head)) {
+            self.game_over(DeathCause::Enemy);
+            return;
+        }
+        // Atravessar a borda não é bater, então ela não conta como parede aqui.
+        let next = self.snake.next_head();
+        if self.is_wall(next) && !(self.config.wrap_edges && self.is_out_of_bounds(next)) {
+            self.events.push(GameEvent::NearWall);
+        }
+    }
+
+    /// Move a cobra do jogador uma célula e resolve comida e colisões.
+    /// Retorna `false` se ela morreu ou ficou parada contra a parede.
+    fn advance_snake(&mut self) -> bool {
+        // No modo de paredes seguras, e no começo da partida enquanto dura a
+        // tolerância, um movimento contra a parede é cancelado e a cobra espera
+        // no lugar até o jogador virar.
+        let mut next = self.snake.next_head();
+        if self.config.wrap_edges && self.is_out_of_bounds(next) {
+            next = self.wrap(next);
+        }
+        if (self.config.wall_bounce || self.start_grace_timer > 0.0) && self.is_wall(next) {
+            // A cauda não saiu de lugar nenhum neste passo.
+            self.snake.tail = None;
+            self.uncover_food();
+            return false;
+        }
+
+        self.trail.push_front(self.snake.head());
+        self.trail.truncate(self.snake.body.len() + TRAIL_LENGTH);
+        self.snake.move_to(next);
+
+        let head = self.snake.head();
+        self.speed_effect = match self.speed_pads.iter().find(|p| p.pos == head) {
+            Some(pad) => Some((pad.kind, SPEED_PAD_TICKS)),
+            None => self.speed_effect.and_then(|(kind, ticks)| (ticks > 1).then_some((kind, ticks - 1))),
+        };
+        let meal_allowed = self.config.lethal_meal == LethalMealPolicy::ScoreThenDie || !self.is_wall(head);
+        if meal_allowed && self.is_food_at(head) {
+            self.grow();
+            let points = 1 + self.extend_chain(head);
+            self.score += points;
+            self.pop_score(head, points as i32);
+            self.events.push(GameEvent::FoodEaten { score: self.score });
+            self.add_food();
+
+            if self.config.special_food && self.special_food.is_none() && self.rng.random::<f32>() < SPECIAL_FOOD_CHANCE
+            {
+                let kind = if self.config.curse_chance > 0.0 && self.rng.random::<f32>() < self.config.curse_chance {
+                    SpecialFoodKind::Curse
+                } else if self.rng.random_bool(0.7) {
+                    SpecialFoodKind::Bonus
+                } else {
+                    SpecialFoodKind::Poison
+                };
+                self.spawn_special_food(kind);
+            }
+        }
+
+        if let Some(special) = self.special_food.take() {
+            if meal_allowed && special.pos == head {
+                match special.kind {
+                    SpecialFoodKind::Bonus => {
+                        self.grow();
+                        self.score += BONUS_POINTS;
+                        self.pop_score(head, BONUS_POINTS as i32);
+                    }
+                    SpecialFoodKind::Poison => {
+                        for _ in 0..POISON_SHRINK {
+                            if self.snake.body.len() > MIN_SNAKE_LENGTH {
+                                self.snake.body.pop_back();
+                            }
+                        }
+                        if self.score > 0 {
+                            self.score -= 1;
+                            self.pop_score(head, -1);
+                        }
+                    }
+                    SpecialFoodKind::Curse => self.inverted_timer = self.config.curse_duration,
+                }
+                self.events.push(GameEvent::SpecialFoodEaten {
+                    kind: special.kind,
+                    score: self.score,
+                });
+            } else {
+                self.special_food = Some(special);
+            }
+        }
+
+        // As vidas só valem contra a borda. Depois de voltar ao centro a cobra
+        // está invencível, e o resto do passo, inimigos inclusive, segue.
+        if self.is_wall(head) {
+            if self.lives > 0 && self.is_out_of_bounds(head) {
+                self.lives -= 1;
+                self.respawn_at_center();
+            } else {
+                self.game_over(if self.is_out_of_bounds(head) { DeathCause::Wall } else { DeathCause::Obstacle });
+                return false;
+            }
+        }
+        if self.snake.is_overlapping_tail() && self.invincible_timer <= 0.0 {
+            self.game_over(DeathCause::Tail);
+            return false;
+        }
+        // Depois de crescer a cauda não deixou célula nenhuma para trás.
+        if self.config.trail_wall_delay > 0.0
+            && let Some(vacated) = self.snake.tail
+        {
+            self.pending_trail_walls.push_back((vacated, self.config.trail_wall_delay));
+        }
+        self.uncover_food();
+        true
+    }
+
+    /// Conta o tempo das células deixadas pela cauda e transforma em obstáculo
+    /// as que venceram. Uma célula que a cobra voltou a ocupar é esquecida:
+    /// quando a cauda sair dela de novo, ela volta para a fila.
+    fn harden_trail_walls(&mut self, dt: f32) {
+        for (_, time_left) in &mut self.pending_trail_walls {
+            *time_left -= dt;
+        }
+        // As células entram na fila em ordem, então as vencidas estão na frente.
+        while let Some(&(cell, time_left)) = self.pending_trail_walls.front()
+            && time_left <= 0.0
+        {
+            self.pending_trail_walls.pop_front();
+            if self.snake.occupies(cell) || self.is_wall(cell) {
+                continue;
+            }
+            self.obstacles.push(cell);
+            self.trail_walls.insert(cell);
+            if self.special_food.as_ref().is_some_and(|f| f.pos == cell) {
+                self.special_food = None;
+            }
+        }
+    }
+
+    /// Tira a comida de baixo de uma parede que se fechou sobre ela. Isso só
+    /// acontece depois do movimento, então a cabeça que chega à maçã no mesmo
+    /// passo segue `lethal_meal`.
+    fn uncover_food(&mut self) {
+        if self.is_wall(self.food) {
+            self.add_food();
+        }
+    }
+
+    /// Conta a maçã comida em `cell` na sequência e devolve os pontos extras.
+    /// A sequência cresce quando a maçã está na mesma linha ou coluna da
+    /// anterior e foi comida até `chain_window` segundos depois dela; cada
+    /// maçã além da primeira vale um ponto a mais que a anterior.
+    fn extend_chain(&mut self, cell: GridPos) -> u32 {
+        if self.config.chain_window <= 0.0 {
+            return 0;
+        }
+        let continues = self.last_meal.is_some_and(|(last, time)| {
+            (last.x == cell.x || last.y == cell.y) && self.run_time - time <= self.config.chain_window
+        });
+        self.chain = if continues { self.chain + 1 } else { 1 };
+        self.last_meal = Some((cell, self.run_time));
+        self.chain - 1
+    }
+
+    /// A sequência em andamento, enquanto a próxima maçã ainda pode continuá-la.
+    fn active_chain(&self) -> Option<u32> {
+        let (_, time) = self.last_meal?;
+        (self.chain > 1 && self.run_time - time <= self.config.chain_window).then_some(self.chain)
+    }
+
+    /// Mostra quantos pontos a cobra acabou de ganhar ou perder nesta célula.
+    fn pop_score(&mut self, cell: GridPos, value: i32) {
+        if self.config.score_popups {
+            self.score_popups.push(ScorePopup { cell, value, ttl: POPUP_DURATION });
+        }
+    }
+
+    /// Devolve à cobra o segmento que a cauda deixou neste passo, a menos que
+    /// ela já tenha o tamanho máximo.
+    fn grow(&mut self) {
+        if self.config.max_length.is_some_and(|max| self.snake.body.len() >= max) {
+            return;
+        }
+        if let Some(tail) = self.snake.tail.take() {
+            self.snake.body.push_back(tail);
+            self.growth_timer = GROWTH_DURATION;
+        }
+    }
+
+    /// Direção escolhida pelo piloto automático: o caminho mais curto até a
+    /// comida ou, sem caminho, qualquer vizinho livre.
+    fn autopilot_direction(&self) -> Option<Direction> {
+        let head = self.snake.head();
+        let blocked = |pos: GridPos| self.is_wall(pos) || self.snake.occupies(pos);
+        ai::search(head, self.food, self.grid_width, self.grid_height, blocked)
+            .first_direction(head)
+            .or_else(|| ai::safe_direction(head, self.snake.direction, blocked))
+    }
+
+    /// Um passo da cobra que anda sozinha atrás do menu: segue a comida como
+    /// os inimigos, sem pontuar, e recomeça do zero quando bate.
+    fn step_menu_preview(&mut self) {
+        let Some(direction) = self.autopilot_direction() else {
+            self.reset_run();
+            return;
+        };
+        self.snake.direction = direction;
+        self.snake.move_forward();
+        let head = self.snake.head();
+        if self.is_wall(head) || self.snake.is_overlapping_tail() {
+            self.reset_run();
+        } else if self.is_food_at(head) {
+            self.grow();
+            self.add_food();
+        }
+    }
+
+    /// Põe a cobra no centro da arena, com o mesmo tamanho, virada para uma
+    /// direção sem parede, e a deixa invencível por um tempo. O corpo começa
+    /// empilhado na cabeça e se estende conforme ela anda.
+    fn respawn_at_center(&mut self) {
+        let center = GridPos::new(self.grid_width / 2, self.grid_height / 2);
+        let start = if self.is_wall(center) {
+            self.free_cells().into_iter().min_by_key(|p| p.manhattan(center)).unwrap_or(center)
+        } else {
+            center
+        };
+        let length = self.snake.body.len();
+        self.snake.direction =
+            ai::safe_direction(start, self.snake.direction, |p| self.is_wall(p)).unwrap_or(self.snake.direction);
+        self.snake.body = std::iter::repeat_n(start, length).collect();
+        self.snake.last_moved = self.snake.direction;
+        self.snake.tail = None;
+        self.input_queue.clear();
+        self.trail.clear();
+        self.invincible_timer = INVINCIBILITY_DURATION;
+    }
+
+    /// Avança vários passos de uma vez, sem desenhar entre eles. Para antes
+    /// se a cobra morrer.
+    fn fast_forward(&mut self) {
+        for _ in 0..FAST_FORWARD_TICKS {
+            if !matches!(self.mode, GameMode::Playing) {
+                break;
+            }
+            self.step();
+        }
+    }
+
+    /// Sai do passo a passo. Os acumuladores recomeçam do zero, como se o
+    /// último passo manual tivesse acabado de acontecer: a sobra de antes da
+    /// pausa não pode disparar um passo logo ao continuar.
+    fn resume_timed_play(&mut self) {
+        if self.step_mode {
+            self.step_mode = false;
+            self.time_since_last_update = 0.0;
+            self.frame_counter = 0;
+            self.step_idle_timer = 0.0;
+        }
+    }
+
+    /// Próxima virada da fila, se ela mudar a direção atual.
+    fn snake_next_turn(&self) -> Option<&Direction> {
+        self.input_queue.front().filter(|&&d| d != self.snake.direction)
+    }
+
+    /// Guarda uma virada para ser aplicada num dos próximos passos. Cada virada
+    /// é validada contra a anterior da fila, assim duas teclas rápidas entre
+    /// dois passos funcionam como esperado. Sem fila, vale a direção em que a
+    /// cobra andou por último, não a pretendida, que pode ter sido cancelada
+    /// por uma parede segura.
+    fn queue_direction(&mut self, direction: Direction) {
+        if direction == self.input_queue.back().copied().unwrap_or(self.snake.direction) {
+            return;
+        }
+        let last = self.input_queue.back().copied().unwrap_or(self.snake.last_moved);
+        if direction == last.opposite() {
+            if self.input_queue.is_empty() {
+                if self.config.reverse_policy == ReversePolicy::Lethal {
+                    self.game_over(DeathCause::Reversed);
+                } else if self.config.reverse_warning {
+                    // A tecla chegou, mas foi recusada: a cabeça pisca para avisar.
+                    self.reverse_warning_timer = REVERSE_WARNING_DURATION;
+                }
+            } else {
+                // Duas viradas contraditórias antes do mesmo passo (por exemplo,
+                // esquerda e direita subindo) se anulam e a cobra segue reto.
+                self.input_queue.pop_back();
+            }
+            return;
+        }
+        if self.input_queue.len() < MAX_QUEUED_TURNS {
+            self.input_queue.push_back(direction);
+            self.try_instant_turn();
+        }
+    }
+
+    /// Com as viradas imediatas, uma virada que chega depois de
+    /// `INSTANT_TURN_THRESHOLD` do intervalo anda na hora, sem esperar o
+    /// passo agendado. O intervalo inteiro é descontado do acumulador, que
+    /// fica negativo e atrasa o passo seguinte na mesma medida: dois passos
+    /// nunca ficam a menos de `INSTANT_TURN_THRESHOLD` intervalo um do outro,
+    /// e a média nunca passa de um passo por intervalo.
+    fn try_instant_turn(&mut self) {
+        if !self.config.instant_turns
+            || !matches!(self.mode, GameMode::Playing)
+            || self.step_mode
+            || self.console.open
+            || self.intro_timer > 0.0
+            || self.config.frames_per_tick > 0
+        {
+            return;
+        }
+        let mut interval = self.update_interval();
+        if self.slow_motion {
+            interval *= SLOW_MOTION_FACTOR;
+        }
+        if self.time_since_last_update >= interval * INSTANT_TURN_THRESHOLD {
+            self.time_since_last_update -= interval;
+            self.step();
+        }
+    }
+
+    /// Zera o placar e a maior pontuação das estatísticas, nos arquivos também.
+    fn reset_scores(&mut self, ctx: &Context) {
+        if let Err(e) = self.leaderboard.reset(ctx) {
+            eprintln!("Não foi possível zerar o placar: {}", e);
+        }
+        self.stats.best_score = 0;
+        if let Err(e) = self.stats.save(ctx) {
+            eprintln!("Não foi possível salvar as estatísticas: {}", e);
+        }
+    }
+
+    fn save_config(&self, ctx: &Context) {
+        if let Err(e) = self.config.save(ctx) {
+            eprintln!("Não foi possível salvar as configurações: {}", e);
+        }
+    }
+
+    /// Altera a opção selecionada. A resolução vale na hora: a janela muda e
+    /// `resize_event` recalcula a grade.
+    fn adjust_setting(&mut self, ctx: &mut Context, delta: i32) {
+        let setting = SETTINGS[self.settings_selection];
+        setting.adjust(&mut self.config, delta, &self.themes);
+        if setting == Setting::Resolution
+            && let Err(e) = ctx.gfx.set_mode(self.config.window_mode())
+        {
+            eprintln!("Não foi possível mudar a resolução: {}", e);
+        }
+    }
+
+    /// Salva as configurações e volta para o menu.
+    fn leave_settings(&mut self, ctx: &Context) {
+        self.save_config(ctx);
+        // O tamanho da arena pode ter mudado.
+        self.resize_grid(ctx);
+        self.mode = GameMode::Menu;
+    }
+
+    /// Recalcula a grade para a janela atual sem encerrar a partida.
+    fn resize_grid(&mut self, ctx: &Context) {
+        let (screen_w, screen_h) = ui_size(ctx, &self.config);
+        (self.grid_width, self.grid_height) = self.config.grid_size(screen_w, screen_h, BLOCK_SIZE);
+        self.fit_to_grid();
+    }
+
+    /// Centraliza a câmera na cabeça da cobra sem mostrar nada além das bordas.
+    /// Num eixo em que a arena é menor que a janela, a sobra (menos de uma
+    /// célula) vira margem dos dois lados e a arena fica no meio.
+    fn update_camera(&mut self, view_w: f32, view_h: f32) {
+        let head = cell_to_pixel(self.snake.head()) + Vec2::splat(BLOCK_SIZE * 0.5);
+        let axis = |head: f32, view: f32, cells: i32| {
+            let arena = cells as f32 * BLOCK_SIZE;
+            if arena <= view {
+                (arena - view) * 0.5
+            } else {
+                (head - view * 0.5).clamp(0.0, arena - view)
+            }
+        };
+        self.camera = Rect::new(
+            axis(head.x, view_w, self.grid_width),
+            axis(head.y, view_h, self.grid_height),
+            view_w,
+            view_h,
+        );
+    }
+
+    /// Muda o tamanho das células e recalcula a grade sem encerrar a partida.
+    fn change_cell_scale(&mut self, ctx: &Context, step: f32) {
+        let scale = (self.config.cell_scale + step).clamp(MIN_CELL_SCALE, MAX_CELL_SCALE);
+        if scale == self.config.cell_scale {
+            return;
+        }
+        self.config.cell_scale = scale;
+        self.save_config(ctx);
+        self.resize_grid(ctx);
+    }
+
+    /// Aproxima ou afasta a câmera; a grade e a partida continuam iguais.
+    fn change_zoom(&mut self, ctx: &Context, step: f32) {
+        let zoom = (self.config.zoom + step).clamp(MIN_ZOOM, MAX_ZOOM);
+        if zoom == self.config.zoom {
+            return;
+        }
+        self.config.zoom = zoom;
+        self.save_config(ctx);
+    }
+
+    /// Traz para dentro da arena tudo o que ficou fora depois de a grade mudar.
+    fn fit_to_grid(&mut self) {
+        let (max_x, max_y) = ((self.grid_width - 2).max(1), (self.grid_height - 2).max(1));
+        let clamp = |b: &mut GridPos| {
+            b.x = b.x.clamp(1, max_x);
+            b.y = b.y.clamp(1, max_y);
+        };
+        self.snake.body.iter_mut().for_each(clamp);
+        for enemy in &mut self.enemies {
+            enemy.body.iter_mut().for_each(clamp);
+        }
+        self.trail.iter_mut().for_each(clamp);
+        self.obstacles.retain(|b| b.x <= max_x && b.y <= max_y);
+        self.speed_pads.retain(|p| p.pos.x <= max_x && p.pos.y <= max_y);
+        if self.special_food.as_ref().is_some_and(|f| f.pos.x > max_x || f.pos.y > max_y) {
+            self.special_food = None;
+        }
+        if self.food.x > max_x || self.food.y > max_y {
+            self.add_food();
+        }
+    }
+
+    /// Avança o jogo um quadro.
+    fn update_frame(&mut self, ctx: &mut Context) -> GameResult {
+        self.process_events(ctx);
+
+        let track = match self.mode {
+            GameMode::Playing | GameMode::SplitScreen => Some(Track::Game),
+            GameMode::DeathReplay | GameMode::GameOver => None,
+            _ => Some(Track::Menu),
+        };
+        self.music.update(ctx, track, self.config.music_volume())?;
+
+        if let Some((_, time_left)) = self.cheat_flash.as_mut() {
+            *time_left -= ctx.time.delta().as_secs_f32();
+            if *time_left <= 0.0 {
+                self.cheat_flash = None;
+            }
+        }
+
+        self.particles.update(ctx.time.delta().as_secs_f32());
+        for popup in &mut self.score_popups {
+            popup.ttl -= ctx.time.delta().as_secs_f32();
+        }
+        self.score_popups.retain(|p| p.ttl > 0.0);
+
+        if matches!(self.mode, GameMode::DeathReplay) {
+            self.replay_timer += ctx.time.delta().as_secs_f32();
+            if self.replay_timer >= REPLAY_DURATION {
+                self.finish_death_replay();
+            }
+            return Ok(());
+        }
+
+        if let Some(race) = self.race.as_mut() {
+            if board::race_result(race).is_none() {
+                self.race_timer += ctx.time.delta().as_secs_f32();
+                if self.race_timer >= board::STEP_INTERVAL {
+                    self.race_timer -= board::STEP_INTERVAL;
+                    race.iter_mut().for_each(Board::step);
+                }
+            }
+            return Ok(());
+        }
+
+        if matches!(self.mode, GameMode::GameOver) && self.config.auto_return_delay > 0 {
+            self.game_over_timer += ctx.time.delta().as_secs_f32();
+            if self.game_over_timer >= self.config.auto_return_delay as f32 {
+                self.reset_to_menu(ctx);
+            }
+            return Ok(());
+        }
+
+        if matches!(self.mode, GameMode::Menu) && self.config.menu_preview {
+            self.time_since_last_update += ctx.time.delta().as_secs_f32();
+            let interval = self.update_interval();
+            if self.time_since_last_update >= interval {
+                self.time_since_last_update = (self.time_since_last_update - interval).min(interval);
+                self.step_menu_preview();
+            }
+            return Ok(());
+        }
+
+        if !matches!(self.mode, GameMode::Playing) {
+            return Ok(());
+        }
+
+        // A partida fica parada enquanto o console está aberto.
+        if self.console.open {
+            return Ok(());
+        }
+
+        // No ritmo por quadros todo quadro vale o mesmo tempo, para que nada
+        // na partida dependa da duração real de cada um.
+        let dt = if self.config.frames_per_tick > 0 { FIXED_FRAME_TIME } else { ctx.time.delta().as_secs_f32() };
+
+        // Durante a animação de entrada nada anda, nem os cronômetros da partida.
+        if self.intro_timer > 0.0 {
+            self.intro_timer = (self.intro_timer - dt).max(0.0);
+            return Ok(());
+        }
+
+        self.dash_cooldown = (self.dash_cooldown - dt).max(0.0);
+        if let Some(charge) = self.dash_charge.as_mut() {
+            *charge += dt;
+        }
+        self.growth_timer = (self.growth_timer - dt).max(0.0);
+        self.invincible_timer = (self.invincible_timer - dt).max(0.0);
+        self.start_grace_timer = (self.start_grace_timer - dt).max(0.0);
+        self.inverted_timer = (self.inverted_timer - dt).max(0.0);
+        self.reverse_warning_timer = (self.reverse_warning_timer - dt).max(0.0);
+        self.tongue_timer = (self.tongue_timer + dt) % TONGUE_INTERVAL;
+
+        if self.step_mode {
+            if self.pending_steps > 0 {
+                self.pending_steps -= 1;
+                self.step();
+                self.step_idle_timer = 0.0;
+            } else {
+                self.step_idle_timer += dt;
+            }
+            return Ok(());
+        }
+
+        if self.config.shrinking_arena {
+            self.ring_shrink_timer += dt;
+            if self.ring_shrink_timer >= RING_SHRINK_INTERVAL {
+                self.ring_shrink_timer = 0.0;
+                if self.shrink_arena() && self.snake.body.iter().any(|&b| self.is_wall(b)) {
+                    self.game_over(DeathCause::ArenaClosed);
+                    return Ok(());
+                }
+            }
+        }
+
+        if let Some(special) = self.special_food.as_mut() {
+            special.ttl -= dt;
+            if special.ttl <= 0.0 {
+                self.special_food = None;
+            }
+        }
+
+        if !self.pending_trail_walls.is_empty() {
+            self.harden_trail_walls(dt);
+        }
+
+        if self.config.moving_food && self.config.food_enabled {
+            self.food_move_timer += dt;
+            if self.food_move_timer >= FOOD_MOVE_INTERVAL {
+                self.food_move_timer = 0.0;
+                self.step_food();
+            }
+        }
+
+        if self.config.food_magnet_interval > 0.0 && self.config.food_enabled {
+            self.magnet_timer += dt;
+            if self.magnet_timer >= self.config.food_magnet_interval {
+                self.magnet_timer = 0.0;
+                self.pull_food();
+            }
+        }
+
+        if self.config.frames_per_tick > 0 {
+            let mut frames = self.config.frames_per_tick as f32;
+            if self.slow_motion {
+                frames *= SLOW_MOTION_FACTOR;
+            }
+            self.frame_counter += 1;
+            if self.frame_counter as f32 >= frames.round() {
+                self.frame_counter = 0;
+                self.step();
+            }
+        } else if self.advance_clock(dt) {
+            self.step();
+        }
+
+        self.run_time += dt;
+        if self.score_history.last().is_none_or(|&(_, score)| score != self.score) {
+            self.score_history.push((self.run_time, self.score));
+        }
+        Ok(())
+    }
+
+    /// Soma `dt` ao acumulador do movimento e diz se chegou a hora de um passo.
+    /// O que sobra do intervalo passa para o próximo passo, para o ritmo não
+    /// depender da taxa de quadros. Depois de um travamento longo a sobra é
+    /// limitada a um passo, em vez de disparar vários de uma vez.
+    fn advance_clock(&mut self, dt: f32) -> bool {
+        self.time_since_last_update += dt;
+        let mut update_interval = self.update_interval();
+        if self.slow_motion {
+            update_interval *= SLOW_MOTION_FACTOR;
+        }
+        if self.time_since_last_update < update_interval {
+            return false;
+        }
+        self.time_since_last_update = (self.time_since_last_update - update_interval).min(update_interval);
+        true
+    }
+
+    /// Quanto do intervalo até o próximo passo já passou, de 0.0 a 1.0.
+    fn step_progress(&self) -> f32 {
+        let progress = if self.config.frames_per_tick > 0 {
+            self.frame_counter as f32 / self.config.frames_per_tick as f32
+        } else {
+            self.time_since_last_update / self.update_interval()
+        };
+        let slow = if self.slow_motion { SLOW_MOTION_FACTOR } else { 1.0 };
+        (progress / slow).clamp(0.0, 1.0)
+    }
+
+    /// Cor do chão da arena: a do tema, tingida pela hora do dia quando o
+    /// ciclo está ligado.
+    fn floor_color(&self, ctx: &Context, color: Color) -> Color {
+        if self.config.day_cycle_minutes == 0 {
+            return color;
+        }
+        let cycle = self.config.day_cycle_minutes as f32 * 60.0;
+        let phase = (ctx.time.time_since_start().as_secs_f32() / cycle).fract() * DAY_PHASES.len() as f32;
+        let (from, to) = (DAY_PHASES[phase as usize], DAY_PHASES[(phase as usize + 1) % DAY_PHASES.len()]);
+        let tint = |i: usize| from[i] + (to[i] - from[i]) * phase.fract();
+        Color::new((color.r * tint(0)).min(1.0), (color.g * tint(1)).min(1.0), (color.b * tint(2)).min(1.0), color.a)
+    }
+
+    /// Desenha um quadro.
+    fn draw_frame(&mut self, ctx: &mut Context) -> GameResult {
+        let background = if self.config.high_visibility {
+            Color::BLACK
+        } else {
+            self.floor_color(ctx, self.theme().background)
+        };
+        let mut canvas = graphics::Canvas::from_frame(ctx, background);
+        let (screen_w, screen_h) = ui_size(ctx, &self.config);
+
+        // O mundo é desenhado em unidades de BLOCK_SIZE; a escala das células,
+        // o zoom e a câmera entram só na projeção. Textos e menus voltam às
+        // unidades da interface, que a escala HiDPI converte em pixels físicos.
+        // A tela dividida tem grades próprias e ignora o zoom.
+        let zoom = if matches!(self.mode, GameMode::SplitScreen) { 1.0 } else { self.config.zoom };
+        let scale = self.config.cell_scale * zoom;
+        self.update_camera(screen_w / scale, screen_h / scale);
+        canvas.set_screen_coordinates(self.camera);
+        if !matches!(self.mode, GameMode::SplitScreen) {
+            draw_background(self, ctx, &mut canvas)?;
+        }
+        let preview = matches!(self.mode, GameMode::Menu) && self.config.menu_preview;
+        if preview || matches!(self.mode, GameMode::Playing | GameMode::DeathReplay | GameMode::GameOver) {
+            draw_gameplay(self, ctx, &mut canvas)?;
+        }
+        canvas.set_screen_coordinates(Rect::new(0.0, 0.0, screen_w, screen_h));
+
+        match self.mode {
+            GameMode::Menu => {
+                draw_menu(self, ctx, &mut canvas)?;
+            }
+            GameMode::Settings => {
+                draw_settings(self, ctx, &mut canvas)?;
+            }
+            GameMode::Leaderboard => {
+                draw_leaderboard(self, ctx, &mut canvas)?;
+            }
+            GameMode::Stats => {
+                draw_stats(self, ctx, &mut canvas)?;
+            }
+            GameMode::Help => {
+                draw_help(self, ctx, &mut canvas)?;
+            }
+            GameMode::ImportChallenge => {
+                draw_import_challenge(self, ctx, &mut canvas)?;
+            }
+            GameMode::SplitScreen => {
+                draw_split_screen(self, ctx, &mut canvas)?;
+            }
+            GameMode::Playing | GameMode::DeathReplay => {
+                draw_hud(self, ctx, &mut canvas)?;
+                if self.dpad_visible() {
+                    draw_dpad(self, ctx, &mut canvas)?;
+                }
+            }
+            GameMode::GameOver => {
+                draw_hud(self, ctx, &mut canvas)?;
+                // O aviso de fim de jogo espera a explosão terminar.
+                if self.particles.is_empty() {
+                    draw_game_over(self, ctx, &mut canvas)?;
+                }
+            }
+        }
+
+        if self.debug {
+            draw_debug(self, ctx, &mut canvas)?;
+        }
+        if self.console.open {
+            draw_console(self, ctx, &mut canvas)?;
+        }
+
+        canvas.finish(ctx)?;
+
+        // Um erro de disco encerra a gravação, mas não a partida.
+        if let Some(recording) = self.recording.as_mut()
+            && let Err(e) = recording.capture(ctx, ctx.time.delta().as_secs_f32())
+        {
+            eprintln!("Gravação interrompida: {}", e);
+            self.stop_recording(ctx);
+        }
+        Ok(())
+    }
+
+    /// Liga ou desliga a gravação de quadros.
+    fn toggle_recording(&mut self, ctx: &Context) {
+        if self.recording.is_some() {
+            self.stop_recording(ctx);
+            return;
+        }
+        match Recording::start(ctx) {
+            Ok(recording) => self.recording = Some(recording),
+            Err(e) => eprintln!("Não foi possível começar a gravação: {}", e),
+        }
+    }
+
+    /// Encerra a gravação, se houver uma, e escreve o manifesto.
+    fn stop_recording(&mut self, ctx: &Context) {
+        if let Some(recording) = self.recording.take() {
+            let dir = recording.dir().to_string();
+            match recording.finish(ctx) {
+                Ok(()) => println!("Gravação salva em {}", dir),
+                Err(e) => eprintln!("Não foi possível salvar o manifesto da gravação: {}", e),
+            }
+        }
+    }
+}
+
+impl EventHandler for GameState {
+    // O tempo de cada parte do quadro é medido para a sobreposição de depuração.
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        let started = Instant::now();
+        let result = self.update_frame(ctx);
+        self.frame_times.record_update(started.elapsed().as_secs_f32());
+        result
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        let started = Instant::now();
+        let result = self.draw_frame(ctx);
+        self.frame_times.record_draw(started.elapsed().as_secs_f32());
+        result
+    }
+
+    // Mudar a janela de monitor também pode trocar o fator de escala; o
+    // tamanho em unidades da interface já o leva em conta.
+    fn resize_event(&mut self, ctx: &mut Context, _width: f32, _height: f32) -> GameResult {
+        let (screen_w, screen_h) = ui_size(ctx, &self.config);
+        (self.grid_width, self.grid_height) = self.config.grid_size(screen_w, screen_h, BLOCK_SIZE);
+        // A partida continua, com o que ficou fora trazido para a grade nova.
+        // As arenas da corrida têm tamanho fixo, então ela volta ao menu.
+        if matches!(self.mode, GameMode::SplitScreen) {
+            self.reset_to_menu(ctx);
+        } else {
+            self.fit_to_grid();
+        }
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, ctx: &mut Context, input: KeyInput, repeat: bool) -> GameResult {
+        if let Some(keycode) = input.keycode {
+            // Ctrl+Q fecha o jogo de qualquer tela; o que falta salvar fica para `quit_event`.
+            if keycode == KeyCode::Q && input.mods.contains(KeyMods::CTRL) {
+                ctx.request_quit();
+                return Ok(());
+            }
+            if keycode == KeyCode::F3 && !repeat {
+                self.debug = !self.debug;
+                if !self.debug {
+                    self.resume_timed_play();
+                    self.console.open = false;
+                    self.stop_recording(ctx);
+                }
+                return Ok(());
+            }
+            if keycode == KeyCode::F9 && self.debug && !repeat {
+                self.toggle_recording(ctx);
+                return Ok(());
+            }
+
+            // Com o console aberto o teclado só edita a linha de comando.
+            if self.console.open {
+                match keycode {
+                    KeyCode::Grave | KeyCode::Escape => self.console.open = false,
+                    KeyCode::Return | KeyCode::NumpadEnter => self.run_console_command(),
+                    KeyCode::Back => {
+                        self.console.input.pop();
+                    }
+                    _ => {}
+                }
+                return Ok(());
+            }
+            if keycode == KeyCode::Grave && self.debug && !repeat {
+                self.console.open = true;
+                return Ok(());
+            }
+
+            let scale_step = match keycode {
+                KeyCode::Equals | KeyCode::Plus | KeyCode::NumpadAdd => Some(CELL_SCALE_STEP),
+                KeyCode::Minus | KeyCode::NumpadSubtract => Some(-CELL_SCALE_STEP),
+                _ => None,
+            };
+            if let Some(step) = scale_step
+                && !matches!(self.mode, GameMode::Settings | GameMode::ImportChallenge)
+            {
+                self.change_cell_scale(ctx, step);
+                return Ok(());
+            }
+            let zoom_step = match keycode {
+                KeyCode::RBracket => Some(ZOOM_STEP),
+                KeyCode::LBracket => Some(-ZOOM_STEP),
+                _ => None,
+            };
+            if let Some(step) = zoom_step
+                && !matches!(self.mode, GameMode::Settings | GameMode::ImportChallenge)
+            {
+                self.change_zoom(ctx, step);
+                return Ok(());
+            }
+
+            match self.mode {
+                GameMode::Menu => {
+                    let typing_cheat = !repeat && self.track_cheat_key(keycode);
+                    // Esquerda ou direita começam a partida com a cobra virada para
+                    // esse lado. Cima e baixo continuam navegando, e nenhuma tecla
+                    // começa a partida no meio de um código secreto.
+                    if self.config.direction_starts
+                        && !repeat
+                        && MENU_ITEMS[self.menu_selection] == MenuItem::Play
+                        && !typing_cheat
+                        && let Some(d) = key_direction(self.config.controls, keycode)
+                        && matches!(d, Direction::Left | Direction::Right)
+                    {
+                        self.restart_facing(d);
+                        return Ok(());
+                    }
+                    match keycode {
+                        KeyCode::Tab => {
+                            self.menu_selection = wrap_selection(self.menu_selection, 1, MENU_ITEMS.len());
+                        }
+                        KeyCode::Up | KeyCode::W => {
+                            self.menu_selection = wrap_selection(self.menu_selection, -1, MENU_ITEMS.len());
+                        }
+                        KeyCode::Down | KeyCode::S => {
+                            self.menu_selection = wrap_selection(self.menu_selection, 1, MENU_ITEMS.len());
+                        }
+                        KeyCode::Return => self.activate_menu_item(ctx),
+                        _ => {}
+                    }
+                }
+                GameMode::Settings => match keycode {
+                    KeyCode::Up | KeyCode::W => {
+                        self.settings_selection = wrap_selection(self.settings_selection, -1, SETTINGS.len());
+                    }
+                    KeyCode::Down | KeyCode::S => {
+                        self.settings_selection = wrap_selection(self.settings_selection, 1, SETTINGS.len());
+                    }
+                    KeyCode::Left | KeyCode::A => self.adjust_setting(ctx, -1),
+                    KeyCode::Right | KeyCode::D | KeyCode::Return => self.adjust_setting(ctx, 1),
+                    KeyCode::Escape => self.leave_settings(ctx),
+                    _ => {}
+                },
+                GameMode::ImportChallenge => match keycode {
+                    KeyCode::Return | KeyCode::NumpadEnter => match Challenge::decode(&self.challenge_input) {
+                        Some(challenge) => self.play_challenge(ctx, challenge),
+                        None => self.challenge_error = true,
+                    },
+                    KeyCode::Back => {
+                        self.challenge_input.pop();
+                    }
+                    KeyCode::Escape => self.mode = GameMode::Menu,
+                    _ => {}
+                },
+                GameMode::SplitScreen => {
+                    let Some(race) = self.race.as_mut() else {
+                        return Ok(());
+                    };
+                    if repeat {
+                        return Ok(());
+                    }
+                    // O jogador da esquerda usa WASD; o da direita, as setas. Com a
+                    // corrida encerrada, qualquer tecla volta ao menu.
+                    if board::race_result(race).is_some() || keycode == KeyCode::Escape {
+                        self.reset_to_menu(ctx);
+                    } else if let Some(d) = key_direction(ControlScheme::Wasd, keycode) {
+                        race[0].turn(d);
+                    } else if let Some(d) = key_direction(ControlScheme::Arrows, keycode) {
+                        race[1].turn(d);
+                    }
+                }
+                GameMode::Leaderboard if self.confirm_reset && !repeat => {
+                    // Só S confirma; qualquer outra tecla desiste.
+                    if keycode == KeyCode::S {
+                        self.reset_scores(ctx);
+                    }
+                    self.confirm_reset = false;
+                }
+                GameMode::Leaderboard if keycode == KeyCode::R => self.confirm_reset = true,
+                GameMode::Leaderboard | GameMode::Stats | GameMode::Help => {
+                    if matches!(keycode, KeyCode::Escape | KeyCode::Return) {
+                        self.mode = GameMode::Menu;
+                    }
+                }
+                GameMode::Playing => {
+                    // Só a primeira pressão conta: a repetição automática de uma
+                    // tecla segurada não deve gerar novas viradas.
+                    if repeat {
+                        return Ok(());
+                    }
+
+                    if keycode == KeyCode::Z {
+                        self.slow_motion = !self.slow_motion;
+                    }
+                    if keycode == KeyCode::LShift && self.dash_cooldown <= 0.0 && !self.dash_pending {
+                        self.dash_charge = Some(0.0);
+                    }
+
+                    // Teclas de teste, disponíveis só com a depuração ligada.
+                    if self.debug {
+                        match keycode {
+                            KeyCode::Key1 => self.spawn_special_food(SpecialFoodKind::Bonus),
+                            KeyCode::Key2 => self.spawn_special_food(SpecialFoodKind::Poison),
+                            KeyCode::Key3 => self.spawn_obstacle(),
+                            KeyCode::Period => {
+                                self.step_mode = true;
+                                self.pending_steps += 1;
+                            }
+                            KeyCode::Space => self.resume_timed_play(),
+                            KeyCode::F => self.fast_forward(),
+                            _ => {}
+                        }
+                    }
+
+                    if let Some(d) = key_direction(self.config.controls, keycode) {
+                        self.queue_direction(self.steer(d));
+                    }
+                }
+                GameMode::DeathReplay => {
+                    if !repeat {
+                        self.finish_death_replay();
+                    }
+                }
+                GameMode::GameOver => {
+                    // Uma tecla que ainda estava segurada ao morrer não fecha a tela.
+                    // Enquanto a explosão acontece, a tecla só a encerra.
+                    if !repeat && !self.particles.is_empty() {
+                        self.particles.clear();
+                    } else if !repeat {
+                        self.reset_to_menu(ctx);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn quit_event(&mut self, ctx: &mut Context) -> GameResult<bool> {
+        // Uma morte deste quadro ainda não chegou ao placar, e as maçãs da
+        // partida em andamento só iriam para o arquivo no fim dela.
+        self.process_events(ctx);
+        if let Err(e) = self.stats.save(ctx) {
+            eprintln!("Não foi possível salvar as estatísticas: {}", e);
+        }
+        self.stop_recording(ctx);
+        Ok(false)
+    }
+
+    fn mouse_button_down_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) -> GameResult {
+        if button == MouseButton::Left {
+            self.press_dpad(ctx, x, y);
+        }
+        Ok(())
+    }
+
+    // Substitui o tratamento padrão, que repassaria o toque como clique, para
+    // notar que há uma tela de toque.
+    fn touch_event(&mut self, ctx: &mut Context, phase: TouchPhase, x: f64, y: f64) -> GameResult {
+        self.touch_detected = true;
+        if phase == TouchPhase::Started {
+            self.press_dpad(ctx, x as f32, y as f32);
+        }
+        Ok(())
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) -> GameResult {
+        // A crase que abre o console também chega aqui e não deve ser digitada.
+        if self.console.open && !character.is_control() && character != '`' {
+            self.console.input.push(character);
+        }
+        // Só os caracteres que podem aparecer num código são aceitos.
+        if matches!(self.mode, GameMode::ImportChallenge)
+            && (character.is_ascii_alphanumeric() || character == '-' || character == '_')
+            && self.challenge_input.len() < MAX_CHALLENGE_CODE_LEN
+        {
+            self.challenge_input.push(character);
+            self.challenge_error = false;
+        }
+        Ok(())
+    }
+
+    fn key_up_event(&mut self, _ctx: &mut Context, input: KeyInput) -> GameResult {
+        // Soltar a tecla de investida antes de carregar não faz nada.
+        if input.keycode == Some(KeyCode::LShift)
+            && let Some(charge) = self.dash_charge.take()
+            && charge >= DASH_CHARGE_TIME
+            && matches!(self.mode, GameMode::Playing)
+        {
+            self.dash_pending = true;
+            self.dash_cooldown = DASH_COOLDOWN;
+        }
+        Ok(())
+    }
+}
+
+/// Move a seleção de uma lista com `len` itens, dando a volta nas pontas.
+/// Numa lista vazia a seleção fica em 0.
+fn wrap_selection(current: usize, delta: i32, len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    (current as i32 + delta).rem_euclid(len as i32) as usize
+}
+
+/// Direção associada à tecla, considerando só as teclas do esquema escolhido.
+fn key_direction(scheme: ControlScheme, keycode: KeyCode) -> Option<Direction> {
+    let arrows = match keycode {
+        KeyCode::Up => Some(Direction::Up),
+        KeyCode::Down => Some(Direction::Down),
+        KeyCode::Left => Some(Direction::Left),
+        KeyCode::Right => Some(Direction::Right),
+        _ => None,
+    };
+    let wasd = match keycode {
+        KeyCode::W => Some(Direction::Up),
+        KeyCode::S => Some(Direction::Down),
+        KeyCode::A => Some(Direction::Left),
+        KeyCode::D => Some(Direction::Right),
+        _ => None,
+    };
+    match scheme {
+        ControlScheme::Both => arrows.or(wasd),
+        ControlScheme::Arrows => arrows,
+        ControlScheme::Wasd => wasd,
+    }
+}
+
+// --- FUNÇÕES DE DESENHO AUXILIARES ---
+
+fn draw_background(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    // No pulso, cada batida clareia as paredes, que voltam à cor do tema até a próxima.
+    let mut wall_color = if gs.config.high_visibility { Color::WHITE } else { gs.theme().wall };
+    if gs.config.wall_pulse {
+        let beats = ctx.time.time_since_start().as_secs_f32() * gs.config.pulse_bpm as f32 / 60.0;
+        let glow = 1.0 + WALL_PULSE_STRENGTH * (1.0 - beats.fract()).powi(2);
+        wall_color = Color::new(
+            (wall_color.r * glow).min(1.0),
+            (wall_color.g * glow).min(1.0),
+            (wall_color.b * glow).min(1.0),
+            wall_color.a,
+        );
+    }
+    let wall_mesh = Mesh::new_rectangle(
+        ctx,
+        DrawMode::fill(),
+        Rect::new(0.0, 0.0, BLOCK_SIZE, BLOCK_SIZE),
+        wall_color,
+    )?;
+    let border_mesh = if gs.config.passable_border {
+        Mesh::new_rectangle(
+            ctx,
+            DrawMode::fill(),
+            Rect::new(0.0, 0.0, BLOCK_SIZE, BLOCK_SIZE),
+            Color::from([0.45, 0.3, 0.1, 1.0]),
+        )?
+    } else {
+        wall_mesh.clone()
+    };
+    // No tabuleiro, as casas escuras são o próprio fundo e só as claras são desenhadas.
+    let grid_style = gs.theme().grid_style;
+    let grid_mode = if grid_style == GridStyle::Checkerboard { DrawMode::fill() } else { DrawMode::stroke(0.5) };
+    let grid_mesh = Mesh::new_rectangle(
+        ctx,
+        grid_mode,
+        Rect::new(0.0, 0.0, BLOCK_SIZE, BLOCK_SIZE),
+        gs.floor_color(ctx, gs.theme().grid),
+    )?;
+
+    // Na entrada da partida as células descem para o lugar e aparecem em
+    // diagonal, do canto superior esquerdo para o inferior direito.
+    let intro = 1.0 - gs.intro_timer / INTRO_DURATION;
+    let diagonal = (gs.grid_width + gs.grid_height) as f32;
+
+    // Só as células dentro da câmera são desenhadas.
+    let first_x = ((gs.camera.x / BLOCK_SIZE) as i32).max(0);
+    let first_y = ((gs.camera.y / BLOCK_SIZE) as i32).max(0);
+    let last_x = (((gs.camera.x + gs.camera.w) / BLOCK_SIZE).ceil() as i32).min(gs.grid_width);
+    let last_y = (((gs.camera.y + gs.camera.h) / BLOCK_SIZE).ceil() as i32).min(gs.grid_height);
+
+    for y in first_y..last_y {
+        for x in first_x..last_x {
+            let delay = (x + y) as f32 / diagonal * 0.5;
+            let progress = ((intro - delay) / 0.5).clamp(0.0, 1.0);
+            let param = DrawParam::new()
+                .dest(Vec2::new(
+                    x as f32 * BLOCK_SIZE,
+                    (y as f32 - (1.0 - progress)) * BLOCK_SIZE,
+                ))
+                .color(Color::new(1.0, 1.0, 1.0, progress));
+            // Na alta visibilidade a grade some, para não competir com a cobra.
+            if x == 0 || x == gs.grid_width - 1 || y == 0 || y == gs.grid_height - 1 {
+                canvas.draw(&border_mesh, param);
+            } else if !gs.config.high_visibility {
+                let floor = match grid_style {
+                    GridStyle::Lines => true,
+                    GridStyle::Checkerboard => (x + y) % 2 == 1,
+                    GridStyle::None => false,
+                };
+                if floor {
+                    canvas.draw(&grid_mesh, param);
+                }
+            }
+        }
+    }
+    // O rastro endurecido tem a cor do corpo escurecida, para se ver de quem é.
+    let body = gs.theme().snake_body;
+    let trail_wall_color = Color::new(body.r * 0.5, body.g * 0.5, body.b * 0.5, 1.0);
+    for block in &gs.obstacles {
+        if gs.trail_walls.contains(block) {
+            canvas.draw(&wall_mesh, DrawParam::new().dest(cell_to_pixel(*block)).color(trail_wall_color));
+        } else {
+            canvas.draw(
+                &wall_mesh,
+                cell_to_pixel(*block),
+            );
+        }
+    }
+
+    // Pisos verdes apontam para a frente (acelera); azuis, para trás (freia).
+    let pad_mesh = Mesh::new_rectangle(
+        ctx,
+        DrawMode::fill(),
+        Rect::new(1.0, 1.0, BLOCK_SIZE - 2.0, BLOCK_SIZE - 2.0),
+        Color::WHITE,
+    )?;
+    let fast_arrow = Mesh::new_polygon(ctx, DrawMode::fill(), &arrow_points(Direction::Right), Color::WHITE)?;
+    let slow_arrow = Mesh::new_polygon(ctx, DrawMode::fill(), &arrow_points(Direction::Left), Color::WHITE)?;
+    for pad in &gs.speed_pads {
+        let (color, arrow) = match pad.kind {
+            PadKind::Fast => (Color::from([0.2, 0.7, 0.3, 0.6]), &fast_arrow),
+            PadKind::Slow => (Color::from([0.2, 0.4, 0.9, 0.6]), &slow_arrow),
+        };
+        let pos = cell_to_pixel(pad.pos);
+        canvas.draw(&pad_mesh, DrawParam::new().dest(pos).color(color));
+        canvas.draw(arrow, DrawParam::new().dest(pos).color(Color::new(1.0, 1.0, 1.0, 0.7)));
+    }
+    Ok(())
+}
+
+/// Canto superior esquerdo da célula, nas coordenadas do mundo.
+fn cell_to_pixel(pos: GridPos) -> Vec2 {
+    Vec2::new(pos.x as f32 * BLOCK_SIZE, pos.y as f32 * BLOCK_SIZE)
+}
+
+/// A maçã comum num dos formatos de tema, branca e com a mesma margem dos blocos.
+fn food_shape_mesh(ctx: &Context, shape: FoodShape) -> GameResult<Mesh> {
+    let center = Vec2::splat(BLOCK_SIZE / 2.0);
+    let radius = BLOCK_SIZE / 2.0 - 2.0;
+    // Pontos em volta do centro, começando no topo, alternando entre os raios.
+    let polygon = |points: usize, radii: &[f32]| -> Vec<Vec2> {
+        (0..points)
+            .map(|i| {
+                let angle = -std::f32::consts::FRAC_PI_2 + std::f32::consts::TAU * i as f32 / points as f32;
+                center + Vec2::new(angle.cos(), angle.sin()) * radii[i % radii.len()]
+            })
+            .collect()
+    };
+    match shape {
+        FoodShape::Square => Mesh::new_rectangle(
+            ctx,
+            DrawMode::fill(),
+            Rect::new(2.0, 2.0, BLOCK_SIZE - 4.0, BLOCK_SIZE - 4.0),
+            Color::WHITE,
+        ),
+        FoodShape::Circle => Mesh::new_circle(ctx, DrawMode::fill(), center, radius, 0.1, Color::WHITE),
+        FoodShape::Diamond => Mesh::new_polygon(ctx, DrawMode::fill(), &polygon(4, &[radius]), Color::WHITE),
+        FoodShape::Star => Mesh::new_polygon(ctx, DrawMode::fill(), &polygon(10, &[radius, radius * 0.45]), Color::WHITE),
+    }
+}
+
+/// O corpo inteiro como uma linha grossa pelo centro das células, numa malha
+/// só. Onde a cobra atravessa uma borda a linha é interrompida, para não
+/// riscar a arena de um lado ao outro.
+fn body_path_mesh(ctx: &Context, body: &LinkedList<GridPos>) -> GameResult<Mesh> {
+    let width = BLOCK_SIZE - 4.0;
+    let stroke = StrokeOptions::default()
+        .with_line_width(width)
+        .with_line_join(LineJoin::Round)
+        .with_line_cap(LineCap::Round);
+    let mut builder = MeshBuilder::new();
+    let mut run: Vec<Vec2> = Vec::new();
+    let mut blocks = body.iter().peekable();
+    while let Some(&block) = blocks.next() {
+        run.push(cell_to_pixel(block) + Vec2::splat(BLOCK_SIZE * 0.5));
+        if blocks.peek().is_some_and(|&&next| next.manhattan(block) == 1) {
+            continue;
+        }
+        // Um trecho de um segmento só vira um círculo do mesmo diâmetro da linha.
+        if let [point] = run[..] {
+            builder.circle(DrawMode::fill(), point, width / 2.0, 0.1, Color::WHITE)?;
+        } else {
+            builder.polyline(DrawMode::Stroke(stroke), &run, Color::WHITE)?;
+        }
+        run.clear();
+    }
+    Ok(Mesh::from_data(ctx, builder.build()))
+}
+
+fn draw_gameplay(gs: &mut GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    let theme = gs.theme().clone();
+    let (head_color, body_color) = (theme.snake_head, theme.snake_body);
+
+    // A margem do tema separa os segmentos; com 0 o corpo fica contínuo.
+    let gap = theme.segment_gap;
+    let block_rect = Rect::new(gap, gap, BLOCK_SIZE - 2.0 * gap, BLOCK_SIZE - 2.0 * gap);
+    let block_mesh = Mesh::new_rectangle(ctx, DrawMode::fill(), block_rect, Color::WHITE)?;
+
+    // Contorno branco grosso da alta visibilidade, no mesmo tamanho dos blocos.
+    let high_vis = gs.config.high_visibility;
+    let outline_mesh = Mesh::new_rectangle(ctx, DrawMode::stroke(HIGH_VIS_OUTLINE), block_rect, Color::WHITE)?;
+    // A alta visibilidade fica com os blocos, mais fáceis de enxergar que letras.
+    let glyphs = theme.glyphs.as_ref().filter(|_| !high_vis);
+
+    // O cone fica embaixo de tudo, como uma luz no chão. O HUD é desenhado
+    // depois, em outra projeção, e nunca fica coberto.
+    if gs.config.vision_cone && gs.replay_body().is_none() && !gs.exploded {
+        let (dx, dy) = gs.snake.direction.offset();
+        let forward = Vec2::new(dx as f32, dy as f32);
+        let side = Vec2::new(-forward.y, forward.x);
+        let eye = cell_to_pixel(gs.snake.head()) + Vec2::splat(BLOCK_SIZE / 2.0);
+        let tip = eye + forward * VISION_CONE_LENGTH * BLOCK_SIZE;
+        let spread = side * VISION_CONE_SPREAD * BLOCK_SIZE;
+        let color = Color::new(theme.snake_head.r, theme.snake_head.g, theme.snake_head.b, 0.12);
+        let cone = Mesh::new_polygon(ctx, DrawMode::fill(), &[eye, tip + spread, tip - spread], color)?;
+        canvas.draw(&cone, DrawParam::new());
+    }
+
+    // Ordem das camadas: comida, inimigos, corpo, rastro e, por último, a cabeça, para
+    // que nada cubra a cabeça quando ela passa por cima de outra coisa.
+    if gs.config.food_enabled && gs.config.moving_food && gs.config.food_trail {
+        for (i, block) in gs.food_trail.iter().enumerate() {
+            let alpha = 0.4 * (1.0 - i as f32 / FOOD_TRAIL_LENGTH as f32);
+            let color = Color::new(theme.food.r, theme.food.g, theme.food.b, alpha);
+            canvas.draw(&block_mesh, DrawParam::new().dest(cell_to_pixel(*block)).color(color));
+        }
+    }
+    if gs.config.food_enabled {
+        let pos = cell_to_pixel(gs.food);
+        if high_vis {
+            // A maçã ocupa a célula inteira, com borda.
+            let food_rect = Rect::new(0.0, 0.0, BLOCK_SIZE, BLOCK_SIZE);
+            let food_mesh = Mesh::new_rectangle(ctx, DrawMode::fill(), food_rect, theme.food)?;
+            let border_mesh = Mesh::new_rectangle(ctx, DrawMode::stroke(HIGH_VIS_OUTLINE), food_rect, Color::WHITE)?;
+            canvas.draw(&food_mesh, pos);
+            canvas.draw(&border_mesh, pos);
+        } else if let Some(glyphs) = glyphs {
+            draw_glyph(ctx, canvas, &glyphs.food, pos, theme.food);
+        } else {
+            gs.refresh_food_mesh(ctx)?;
+            if let Some((_, mesh)) = &gs.food_mesh {
+                canvas.draw(mesh, DrawParam::new().dest(pos).color(theme.food));
+            }
+        }
+    }
+
+    if let Some(special) = &gs.special_food {
+        let color = match special.kind {
+            SpecialFoodKind::Bonus => Color::from([1.0, 0.85, 0.1, 1.0]),
+            SpecialFoodKind::Poison => Color::from([0.6, 0.2, 0.8, 1.0]),
+            SpecialFoodKind::Curse => Color::from([1.0, 0.35, 0.2, 1.0]),
+        };
+        canvas.draw(
+            &block_mesh,
+            DrawParam::new()
+                .dest(cell_to_pixel(special.pos))
+                .color(color),
+        );
+        if gs.config.food_countdown {
+            // O anel começa no topo e perde arco no sentido horário.
+            // Um arco quase nulo daria uma malha vazia, que a ggez recusa.
+            let remaining = (special.ttl / SPECIAL_FOOD_LIFETIME).clamp(0.01, 1.0);
+            let segments = ((COUNTDOWN_SEGMENTS as f32 * remaining).ceil() as usize).max(1);
+            let center = cell_to_pixel(special.pos) + Vec2::splat(BLOCK_SIZE / 2.0);
+            let radius = BLOCK_SIZE * 0.7;
+            let points: Vec<Vec2> = (0..=segments)
+                .map(|i| {
+                    let angle = -std::f32::consts::FRAC_PI_2 + std::f32::consts::TAU * remaining * i as f32 / segments as f32;
+                    center + Vec2::new(angle.cos(), angle.sin()) * radius
+                })
+                .collect();
+            let ring = Mesh::new_polyline(ctx, DrawMode::stroke(2.0), &points, color)?;
+            canvas.draw(&ring, DrawParam::new());
+        }
+    }
+
+    // Busca dos inimigos: células visitadas bem claras e o caminho escolhido
+    // mais forte, para conferir que ele desvia dos corpos.
+    if gs.debug {
+        for search in &gs.ai_searches {
+            for &cell in &search.visited {
+                canvas.draw(&block_mesh, DrawParam::new().dest(cell_to_pixel(cell)).color(Color::new(0.3, 0.8, 1.0, 0.12)));
+            }
+            for &cell in &search.path {
+                canvas.draw(&block_mesh, DrawParam::new().dest(cell_to_pixel(cell)).color(Color::new(1.0, 1.0, 0.3, 0.45)));
+            }
+        }
+    }
+
+    // Durante o rebobinamento a cobra é desenhada a partir dos quadros gravados.
+    for enemy in &gs.enemies {
+        for (i, block) in enemy.body.iter().enumerate() {
+            let color = if i == 0 { theme.enemy_head } else { theme.enemy_body };
+            let pos = cell_to_pixel(*block);
+            canvas.draw(&block_mesh, DrawParam::new().dest(pos).color(color));
+        }
+    }
+
+    // Depois da explosão só restam as partículas no lugar da cobra.
+    if gs.exploded {
+        let particle_mesh = Mesh::new_rectangle(ctx, DrawMode::fill(), Rect::new(-2.0, -2.0, 4.0, 4.0), Color::WHITE)?;
+        for particle in &gs.particles.particles {
+            let color = Color::new(particle.color.r, particle.color.g, particle.color.b, particle.alpha());
+            canvas.draw(&particle_mesh, DrawParam::new().dest(particle.pos).color(color));
+        }
+        return Ok(());
+    }
+
+    if gs.config.body_style == BodyStyle::Path {
+        gs.refresh_body_mesh(ctx)?;
+    }
+    let replay = gs.replay_body();
+    let body = replay.unwrap_or(&gs.snake.body);
+
+    // Com o gradiente, o corpo escurece aos poucos até a ponta da cauda.
+    let segment_color = |i: usize| {
+        if theme.body_gradient {
+            let brightness = 1.0 - (1.0 - TAIL_BRIGHTNESS) * i as f32 / body.len() as f32;
+            Color::new(body_color.r * brightness, body_color.g * brightness, body_color.b * brightness, body_color.a)
+        } else {
+            body_color
+        }
+    };
+    match (glyphs, gs.config.body_style) {
+        (Some(glyphs), _) => {
+            for (i, block) in body.iter().enumerate().skip(1) {
+                draw_glyph(ctx, canvas, &glyphs.body, cell_to_pixel(*block), segment_color(i));
+            }
+        }
+        (None, BodyStyle::Blocks) => {
+            // O último segmento, se acabou de crescer, é desenhado menor e
+            // centralizado na célula até a animação acabar.
+            let growth = if gs.config.growth_animation && replay.is_none() {
+                1.0 - gs.growth_timer / GROWTH_DURATION
+            } else {
+                1.0
+            };
+            // A célula que a cauda acabou de deixar escorrega até o último
+            // segmento durante o passo, em vez de sumir de uma vez. É só
+            // desenho: a grade já não conta essa célula.
+            if gs.config.smooth_tail
+                && replay.is_none()
+                && let (Some(vacated), Some(&last)) = (gs.snake.tail, body.back())
+                && vacated.manhattan(last) == 1
+            {
+                let pos = cell_to_pixel(vacated).lerp(cell_to_pixel(last), gs.step_progress());
+                canvas.draw(&block_mesh, DrawParam::new().dest(pos).color(body_color));
+            }
+            for (i, block) in body.iter().enumerate().skip(1) {
+                let pos = cell_to_pixel(*block);
+                let size = if i == body.len() - 1 { growth } else { 1.0 };
+                let dest = pos + Vec2::splat(BLOCK_SIZE * 0.5 * (1.0 - size));
+                canvas.draw(&block_mesh, DrawParam::new().dest(dest).scale(Vec2::splat(size)).color(segment_color(i)));
+                if high_vis {
+                    canvas.draw(&outline_mesh, DrawParam::new().dest(dest).scale(Vec2::splat(size)));
+                }
+            }
+        }
+        (None, BodyStyle::Path) => {
+            if let Some((_, mesh)) = &gs.body_mesh {
+                canvas.draw(mesh, DrawParam::new().color(body_color));
+            }
+        }
+    }
+
+    if gs.config.trail_enabled && replay.is_none() {
+        // Só as células que a cauda já deixou, que o corpo não cobre; cada uma
+        // mais antiga fica mais transparente que a anterior.
+        let behind_tail = gs.trail.iter().skip(gs.snake.body.len() - 1).take(TRAIL_LENGTH);
+        for (i, block) in behind_tail.enumerate().filter(|(_, b)| !gs.snake.occupies(**b)) {
+            let alpha = 0.35 * (1.0 - i as f32 / TRAIL_LENGTH as f32);
+            let pos = cell_to_pixel(*block);
+            canvas.draw(
+                &block_mesh,
+                DrawParam::new()
+                    .dest(pos)
+                    .color(Color::new(head_color.r, head_color.g, head_color.b, alpha)),
+            );
+        }
+    }
+
+    if replay.is_none() {
+        // Marcas pequenas, centralizadas nas células, para não parecerem comida.
+        for (i, block) in gs.projected_path().iter().enumerate() {
+            let alpha = 0.4 * (1.0 - i as f32 / gs.config.projection_length as f32);
+            let dest = cell_to_pixel(*block) + Vec2::splat(BLOCK_SIZE * 0.35);
+            canvas.draw(
+                &block_mesh,
+                DrawParam::new()
+                    .dest(dest)
+                    .scale(Vec2::splat(0.3))
+                    .color(Color::new(head_color.r, head_color.g, head_color.b, alpha)),
+            );
+        }
+    }
+
+    // A cabeça pisca enquanto a cobra está invencível.
+    let blink = gs.invincible_timer > 0.0 && ((gs.invincible_timer * 8.0) as u32).is_multiple_of(2);
+    let head_color = if blink { Color::new(head_color.r, head_color.g, head_color.b, 0.3) } else { head_color };
+    let head_color = if gs.reverse_warning_timer > 0.0 { Color::new(1.0, 0.15, 0.15, head_color.a) } else { head_color };
+    let pos = cell_to_pixel(*body.front().expect("A cobra não tem corpo."));
+    if let Some(glyphs) = glyphs {
+        // O caractere da cabeça já é o rosto; os olhos ficam de fora.
+        draw_glyph(ctx, canvas, &glyphs.head, pos, head_color);
+    } else {
+        canvas.draw(&block_mesh, DrawParam::new().dest(pos).color(head_color));
+        if high_vis {
+            canvas.draw(&outline_mesh, pos);
+        }
+
+        let centers = eye_centers(gs.snake.direction);
+        for (i, center) in centers.iter().enumerate() {
+            // `forward` aponta para onde a cobra anda; `inward`, para o outro olho.
+            let (dx, dy) = gs.snake.direction.offset();
+            let forward = Vec2::new(dx as f32, dy as f32);
+            let inward = (centers[1 - i] - *center).normalize();
+            let eye = match theme.eyes {
+                EyeStyle::Dot => Mesh::new_circle(ctx, DrawMode::fill(), *center, 2.5, 0.1, Color::BLACK)?,
+                style => Mesh::new_polygon(
+                    ctx,
+                    DrawMode::fill(),
+                    &eye_points(style, *center, forward, inward),
+                    Color::BLACK,
+                )?,
+            };
+            canvas.draw(&eye, pos);
+        }
+    }
+
+    // A língua sai pela borda da frente da cabeça, com a ponta bifurcada.
+    if gs.config.tongue && replay.is_none() && gs.tongue_timer < TONGUE_OUT_TIME {
+        let (dx, dy) = gs.snake.direction.offset();
+        let forward = Vec2::new(dx as f32, dy as f32);
+        let side = Vec2::new(-forward.y, forward.x);
+        let base = pos + Vec2::splat(BLOCK_SIZE / 2.0) + forward * (BLOCK_SIZE / 2.0 - 2.0);
+        let tip = base + forward * 6.0;
+        let tongue_color = Color::from([0.9, 0.1, 0.15, 1.0]);
+        let mut builder = MeshBuilder::new();
+        builder.line(&[base, tip], 2.0, tongue_color)?;
+        builder.line(&[tip, tip + forward * 3.0 + side * 3.0], 1.5, tongue_color)?;
+        builder.line(&[tip, tip + forward * 3.0 - side * 3.0], 1.5, tongue_color)?;
+        canvas.draw(&Mesh::from_data(ctx, builder.build()), DrawParam::new());
+    }
+
+    // Seta discreta mostrando a próxima virada já registrada.
+    if let Some(&next) = gs.snake_next_turn()
+        && replay.is_none()
+    {
+        let arrow = Mesh::new_polygon(
+            ctx,
+            DrawMode::fill(),
+            &arrow_points(next),
+            Color::new(1.0, 1.0, 1.0, 0.5),
+        )?;
+        canvas.draw(&arrow, pos);
+    }
+
+    // Os pontos ganhos ficam por cima de tudo, subindo e sumindo.
+    for popup in &gs.score_popups {
+        let progress = 1.0 - popup.ttl / POPUP_DURATION;
+        let text = CrispText::new(ctx, canvas, format!("{:+}", popup.value), MAIN_FONT, 16.0);
+        let size = text.measure(ctx)?;
+        let pos = cell_to_pixel(popup.cell) + Vec2::new((BLOCK_SIZE - size.x) / 2.0, -POPUP_RISE * progress);
+        let color = if popup.value > 0 { Color::WHITE } else { Color::from([0.8, 0.5, 1.0, 1.0]) };
+        text.draw(canvas, pos, Color::new(color.r, color.g, color.b, 1.0 - progress));
+    }
+
+    Ok(())
+}
+
+/// Pixels físicos por unidade da interface: o fator de escala da janela, a
+/// menos que a configuração force outro.
+fn ui_scale(ctx: &Context, config: &Config) -> f32 {
+    config.effective_ui_scale(ctx.gfx.window().scale_factor() as f32)
+}
+
+/// Tamanho da janela em unidades da interface, a base de toda a disposição
+/// da tela. Numa tela HiDPI é menor que o tamanho em pixels físicos.
+fn ui_size(ctx: &Context, config: &Config) -> (f32, f32) {
+    let (width, height) = ctx.gfx.drawable_size();
+    let scale = ui_scale(ctx, config);
+    (width / scale, height / scale)
+}
+
+/// Um texto medido e posicionado em unidades do canvas, mas rasterizado na
+/// densidade de pixels físicos dele. Sem isso, uma tela HiDPI ou uma câmera
+/// aproximada esticariam as letras e elas sairiam borradas.
+struct CrispText {
+    text: Text,
+    // Pixels físicos por unidade do canvas no momento da criação.
+    density: f32,
+}
+
+impl CrispText {
+    fn new(
+        ctx: &Context,
+        canvas: &graphics::Canvas,
+        content: impl Into<TextFragment>,
+        font: &str,
+        size: f32,
+    ) -> CrispText {
+        let (physical_w, _) = ctx.gfx.drawable_size();
+        let density = canvas.screen_coordinates().map_or(1.0, |screen| physical_w / screen.w);
+        let mut text = Text::new(content);
+        text.set_font(font).set_scale(size * density);
+        CrispText { text, density }
+    }
+
+    fn dimensions(&self, ctx: &Context) -> Option<Rect> {
+        let rect = self.text.dimensions(ctx)?;
+        Some(Rect::new(rect.x / self.density, rect.y / self.density, rect.w / self.density, rect.h / self.density))
+    }
+
+    fn measure(&self, ctx: &Context) -> GameResult<Vec2> {
+        Ok(Vec2::from(self.text.measure(ctx)?) / self.density)
+    }
+
+    fn draw(&self, canvas: &mut graphics::Canvas, pos: Vec2, color: Color) {
+        let param = DrawParam::new().dest(pos).scale(Vec2::splat(1.0 / self.density)).color(color);
+        canvas.draw(&self.text, param);
+    }
+}
+
+/// Desenha `glyph` centralizado na célula que começa em `pos`. Um caractere
+/// largo ou alto demais para `BLOCK_SIZE` é reduzido até caber.
+fn draw_glyph(ctx: &Context, canvas: &mut graphics::Canvas, glyph: &str, pos: Vec2, color: Color) {
+    let mut text = CrispText::new(ctx, canvas, glyph, MAIN_FONT, BLOCK_SIZE);
+    let Some(mut rect) = text.dimensions(ctx) else {
+        return;
+    };
+    let fit = BLOCK_SIZE / rect.w.max(rect.h);
+    if fit < 1.0 {
+        text = CrispText::new(ctx, canvas, glyph, MAIN_FONT, BLOCK_SIZE * fit);
+        let Some(fitted) = text.dimensions(ctx) else {
+            return;
+        };
+        rect = fitted;
+    }
+    let dest = pos + (Vec2::splat(BLOCK_SIZE) - Vec2::new(rect.w, rect.h)) / 2.0 - Vec2::new(rect.x, rect.y);
+    text.draw(canvas, dest, color);
+}
+
+/// Cantos e bordas da tela em que um elemento do HUD pode ser preso.
+#[derive(Clone, Copy, Debug)]
+enum HudAnchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Área da tela reservada ao HUD, em unidades da interface: a parte da
+/// janela coberta pela arena. As margens da centralização ficam de fora, e
+/// numa arena maior que a janela sobra a janela inteira.
+fn hud_safe_area(gs: &GameState, ctx: &Context) -> Rect {
+    let (screen_w, screen_h) = ui_size(ctx, &gs.config);
+    // A mesma projeção de `draw`: as unidades do mundo viram unidades da
+    // interface pela razão entre a janela e a câmera.
+    let scale = if gs.camera.w > 0.0 { screen_w / gs.camera.w } else { 1.0 };
+    let left = (-gs.camera.x * scale).max(0.0);
+    let top = (-gs.camera.y * scale).max(0.0);
+    let right = ((gs.grid_width as f32 * BLOCK_SIZE - gs.camera.x) * scale).min(screen_w);
+    let bottom = ((gs.grid_height as f32 * BLOCK_SIZE - gs.camera.y) * scale).min(screen_h);
+    Rect::new(left, top, (right - left).max(0.0), (bottom - top).max(0.0))
+}
+
+/// Canto superior esquerdo de um elemento de tamanho `size` preso a `anchor`,
+/// a `hud_padding` das bordas da área segura.
+fn hud_position(gs: &GameState, ctx: &Context, anchor: HudAnchor, size: Vec2) -> Vec2 {
+    let area = hud_safe_area(gs, ctx);
+    let padding = gs.config.hud_padding;
+    let left = area.x + padding;
+    let right = area.right() - padding - size.x;
+    let top = area.y + padding;
+    let bottom = area.bottom() - padding - size.y;
+    match anchor {
+        HudAnchor::TopLeft => Vec2::new(left, top),
+        HudAnchor::TopCenter => Vec2::new(area.x + (area.w - size.x) / 2.0, top),
+        HudAnchor::TopRight => Vec2::new(right, top),
+        HudAnchor::BottomLeft => Vec2::new(left, bottom),
+        HudAnchor::BottomRight => Vec2::new(right, bottom),
+    }
+}
+
+/// Multiplicador dos textos do HUD, maior no modo de alta visibilidade.
+fn hud_text_scale(gs: &GameState) -> f32 {
+    if gs.config.high_visibility { HIGH_VIS_TEXT_SCALE } else { 1.0 }
+}
+
+/// Desenha um texto curto do HUD preso a `anchor`.
+fn draw_hud_text(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas, anchor: HudAnchor, text: &str) {
+    let text = CrispText::new(ctx, canvas, text, MAIN_FONT, 16.0 * hud_text_scale(gs));
+    if let Some(text_rect) = text.dimensions(ctx) {
+        let pos = hud_position(gs, ctx, anchor, Vec2::new(text_rect.w, text_rect.h));
+        draw_text(canvas, &text, pos, Color::WHITE, gs.theme().text_shadow);
+    }
+}
+
+/// Textos e ícones da partida, desenhados em coordenadas de tela.
+fn draw_hud(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    let apple_size = BLOCK_SIZE * 0.5;
+    let apple_mesh = Mesh::new_rectangle(
+        ctx,
+        DrawMode::fill(),
+        Rect::new(0.0, 0.0, apple_size, apple_size),
+        Color::RED,
+    )?;
+
+    let score_size = gs.config.score_scale * hud_text_scale(gs);
+    let score_text = CrispText::new(ctx, canvas, gs.config.score_label(gs.score), MAIN_FONT, score_size);
+
+    if let Some(text_rect) = score_text.dimensions(ctx) {
+        // Maçã e pontuação formam um só bloco, alinhados pelo centro. O bloco
+        // fica recuado pela parede do canto, para não cobri-la.
+        let gap = apple_size * 0.5;
+        let height = text_rect.h.max(apple_size);
+        let wall = Vec2::splat(BLOCK_SIZE * gs.config.cell_scale * gs.config.zoom);
+        let size = Vec2::new(apple_size + gap + text_rect.w, height);
+        let origin = hud_position(gs, ctx, HudAnchor::TopLeft, size) + wall;
+        canvas.draw(&apple_mesh, origin + Vec2::new(0.0, (height - apple_size) / 2.0));
+        let pos = origin + Vec2::new(apple_size + gap, (height - text_rect.h) / 2.0);
+        draw_text(canvas, &score_text, pos, Color::WHITE, gs.theme().text_shadow);
+    }
+
+    if gs.slow_motion {
+        draw_hud_text(gs, ctx, canvas, HudAnchor::TopRight, "Câmera lenta");
+    }
+
+    let mut center_items = Vec::new();
+    if let Some(day) = gs.daily {
+        let label = format!("Diário {}", daily::date_label(day));
+        center_items.push(if gs.cheating() { format!("{} (não conta)", label) } else { label });
+    }
+    if gs.config.wall_lives > 0 {
+        center_items.push(format!("Vidas: {}", gs.lives));
+    }
+    if gs.start_grace_timer > 0.0 {
+        center_items.push(format!("Paredes seguras {:.1}s", gs.start_grace_timer));
+    }
+    if gs.step_mode && gs.step_idle_timer >= STEP_IDLE_DELAY {
+        center_items.push("Parado: . avança um passo, Espaço continua".to_string());
+    }
+    if let Some(chain) = gs.active_chain() {
+        center_items.push(format!("Sequência x{}", chain));
+    }
+    if gs.inverted_timer > 0.0 {
+        center_items.push(format!("Controles invertidos! {:.0}s", gs.inverted_timer.ceil()));
+    }
+    if !center_items.is_empty() {
+        draw_hud_text(gs, ctx, canvas, HudAnchor::TopCenter, &center_items.join("   "));
+    }
+
+    if gs.config.speed_gauge {
+        draw_speed_gauge(gs, ctx, canvas)?;
+    }
+    if gs.config.minimap && (gs.config.arena_scale > 1 || gs.config.zoom > 1.0) {
+        draw_minimap(gs, ctx, canvas)?;
+    }
+
+    Ok(())
+}
+
+/// Barra da velocidade no canto inferior direito: vazia no passo inicial,
+/// cheia no mais rápido. Pisos e o bônus de velocidade também a movem.
+fn draw_speed_gauge(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    let fill = ((BASE_INTERVAL - gs.update_interval()) / (BASE_INTERVAL - MIN_INTERVAL)).clamp(0.0, 1.0);
+    let origin = hud_position(gs, ctx, HudAnchor::BottomRight, SPEED_GAUGE_SIZE);
+    if fill > 0.0 {
+        // Do branco ao laranja do título conforme acelera.
+        let color = Color::new(1.0 - 0.1 * fill, 1.0 - 0.5 * fill, 1.0 - 0.8 * fill, 1.0);
+        let bar = Mesh::new_rectangle(
+            ctx,
+            DrawMode::fill(),
+            Rect::new(origin.x, origin.y, SPEED_GAUGE_SIZE.x * fill, SPEED_GAUGE_SIZE.y),
+            color,
+        )?;
+        canvas.draw(&bar, DrawParam::new());
+    }
+    let frame = Mesh::new_rectangle(
+        ctx,
+        DrawMode::stroke(1.0),
+        Rect::new(origin.x, origin.y, SPEED_GAUGE_SIZE.x, SPEED_GAUGE_SIZE.y),
+        Color::WHITE,
+    )?;
+    canvas.draw(&frame, DrawParam::new());
+    Ok(())
+}
+
+/// A arena inteira em miniatura no canto inferior direito, com o retângulo
+/// da câmera marcado.
+fn draw_minimap(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    let cell = MINIMAP_SIZE / gs.grid_width.max(gs.grid_height) as f32;
+    let size = Vec2::new(gs.grid_width as f32, gs.grid_height as f32) * cell;
+    let mut origin = hud_position(gs, ctx, HudAnchor::BottomRight, size);
+    // Com o medidor de velocidade no mesmo canto, o mapa fica acima dele.
+    if gs.config.speed_gauge {
+        origin.y -= SPEED_GAUGE_SIZE.y + gs.config.hud_padding;
+    }
+    // Um quadrado unitário, esticado para cada célula do minimapa.
+    let unit = Mesh::new_rectangle(ctx, DrawMode::fill(), Rect::new(0.0, 0.0, 1.0, 1.0), Color::WHITE)?;
+    canvas.draw(
+        &unit,
+        DrawParam::new()
+            .dest(origin)
+            .scale(Vec2::new(gs.grid_width as f32, gs.grid_height as f32) * cell)
+            .color(Color::new(0.0, 0.0, 0.0, 0.6)),
+    );
+    let mut draw_cell = |pos: GridPos, color: Color| {
+        canvas.draw(
+            &unit,
+            DrawParam::new()
+                .dest(origin + Vec2::new(pos.x as f32, pos.y as f32) * cell)
+                .scale(Vec2::splat(cell))
+                .color(color),
+        );
+    };
+
+    let theme = gs.theme();
+    let wall_color = theme.wall;
+    for &block in &gs.obstacles {
+        draw_cell(block, wall_color);
+    }
+    if gs.config.food_enabled {
+        draw_cell(gs.food, theme.food);
+    }
+    if let Some(special) = &gs.special_food {
+        draw_cell(special.pos, Color::from([1.0, 0.85, 0.1, 1.0]));
+    }
+    for enemy in &gs.enemies {
+        for &block in &enemy.body {
+            draw_cell(block, theme.enemy_head);
+        }
+    }
+    for &block in &gs.snake.body {
+        draw_cell(block, theme.snake_head);
+    }
+
+    let frame = Mesh::new_rectangle(
+        ctx,
+        DrawMode::stroke(1.0),
+        Rect::new(origin.x, origin.y, gs.grid_width as f32 * cell, gs.grid_height as f32 * cell),
+        wall_color,
+    )?;
+    canvas.draw(&frame, DrawParam::new());
+    let view = Mesh::new_rectangle(
+        ctx,
+        DrawMode::stroke(1.0),
+        Rect::new(
+            origin.x + gs.camera.x / BLOCK_SIZE * cell,
+            origin.y + gs.camera.y / BLOCK_SIZE * cell,
+            gs.camera.w / BLOCK_SIZE * cell,
+            gs.camera.h / BLOCK_SIZE * cell,
+        ),
         Color::WHITE,
     )?;
+    canvas.draw(&view, DrawParam::new());
+    Ok(())
+}
+
+/// Centros dos dois olhos dentro da célula da cabeça, na frente dela.
+fn eye_centers(direction: Direction) -> [Vec2; 2] {
+    let (near, far) = (6.0, BLOCK_SIZE - 6.0);
+    match direction {
+        Direction::Up => [Vec2::new(near, near), Vec2::new(far, near)],
+        Direction::Down => [Vec2::new(near, far), Vec2::new(far, far)],
+        Direction::Left => [Vec2::new(near, near), Vec2::new(near, far)],
+        Direction::Right => [Vec2::new(far, near), Vec2::new(far, far)],
+    }
+}
+
+/// Contorno de um olho em volta de `center`, orientado pela direção da
+/// cobra (`forward`) e pelo lado do outro olho (`inward`).
+fn eye_points(style: EyeStyle, center: Vec2, forward: Vec2, inward: Vec2) -> Vec<Vec2> {
+    let (along, across) = match style {
+        EyeStyle::Slit => (3.0, 1.0),
+        _ => (2.0, 2.0),
+    };
+    let front_inner = center + forward * along + inward * across;
+    let front_outer = center + forward * along - inward * across;
+    let back_inner = center - forward * along + inward * across;
+    let back_outer = center - forward * along - inward * across;
+    match style {
+        // A borda da frente desce em direção ao centro da cabeça.
+        EyeStyle::Angry => vec![front_outer, center + inward * across, back_inner, back_outer],
+        _ => vec![front_outer, front_inner, back_inner, back_outer],
+    }
+}
+
+/// Triângulo dentro de uma célula apontando para `direction`.
+fn arrow_points(direction: Direction) -> [Vec2; 3] {
+    let (near, mid, far) = (BLOCK_SIZE * 0.3, BLOCK_SIZE * 0.5, BLOCK_SIZE * 0.7);
+    match direction {
+        Direction::Up => [Vec2::new(mid, near), Vec2::new(far, far), Vec2::new(near, far)],
+        Direction::Down => [Vec2::new(mid, far), Vec2::new(near, near), Vec2::new(far, near)],
+        Direction::Left => [Vec2::new(near, mid), Vec2::new(far, near), Vec2::new(far, far)],
+        Direction::Right => [Vec2::new(far, mid), Vec2::new(near, far), Vec2::new(near, near)],
+    }
+}
+
+/// Botões do direcional na tela, no canto inferior esquerdo, em cruz.
+fn dpad_buttons(gs: &GameState, ctx: &Context) -> [(Direction, Rect); 4] {
+    let origin = hud_position(gs, ctx, HudAnchor::BottomLeft, Vec2::splat(DPAD_BUTTON_SIZE * 3.0));
+    let button = |col: f32, row: f32| {
+        Rect::new(
+            origin.x + col * DPAD_BUTTON_SIZE,
+            origin.y + row * DPAD_BUTTON_SIZE,
+            DPAD_BUTTON_SIZE,
+            DPAD_BUTTON_SIZE,
+        )
+    };
+    [
+        (Direction::Up, button(1.0, 0.0)),
+        (Direction::Left, button(0.0, 1.0)),
+        (Direction::Right, button(2.0, 1.0)),
+        (Direction::Down, button(1.0, 2.0)),
+    ]
+}
+
+fn draw_dpad(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    let button_mesh = Mesh::new_rounded_rectangle(
+        ctx,
+        DrawMode::fill(),
+        Rect::new(2.0, 2.0, DPAD_BUTTON_SIZE - 4.0, DPAD_BUTTON_SIZE - 4.0),
+        8.0,
+        Color::from([1.0, 1.0, 1.0, 0.2]),
+    )?;
+    for (direction, rect) in dpad_buttons(gs, ctx) {
+        let arrow = Mesh::new_polygon(ctx, DrawMode::fill(), &arrow_points(direction), Color::from([1.0, 1.0, 1.0, 0.6]))?;
+        canvas.draw(&button_mesh, DrawParam::new().dest(rect.point()));
+        canvas.draw(&arrow, DrawParam::new().dest(rect.point()).scale(Vec2::splat(DPAD_BUTTON_SIZE / BLOCK_SIZE)));
+    }
+    Ok(())
+}
+
+fn draw_debug(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    let lines = [
+        format!("FPS: {:.0}", ctx.time.fps()),
+        format!(
+            "Update: {:.2}ms  Draw: {:.2}ms",
+            gs.frame_times.update * 1000.0,
+            gs.frame_times.draw * 1000.0
+        ),
+        format!("Intervalo: {:.3}s", gs.update_interval()),
+        format!("Tamanho: {}", gs.snake.body.len()),
+        format!("Semente: {}", gs.seed),
+        format!("Passo a passo: {}", if gs.step_mode { "sim" } else { "não" }),
+        format!(
+            "Evento: {}",
+            gs.last_event.as_ref().map_or("-".to_string(), GameEvent::describe)
+        ),
+        "1: bônus  2: veneno  3: obstáculo".to_string(),
+        ".: um passo  Espaço: continuar  F: 100 passos  `: console".to_string(),
+        match &gs.recording {
+            Some(recording) => format!("F9: parar gravação ({} quadros)", recording.frame_count()),
+            None => "F9: gravar quadros".to_string(),
+        },
+    ];
+    // O bloco fica preso ao canto inferior esquerdo, recuado pela parede da borda.
+    let origin = hud_position(gs, ctx, HudAnchor::BottomLeft, Vec2::new(0.0, lines.len() as f32 * 16.0 + BLOCK_SIZE));
+    for (i, line) in lines.iter().enumerate() {
+        let text = CrispText::new(ctx, canvas, line.as_str(), DEBUG_FONT, 14.0);
+        // A linha dos tempos pisca em vermelho quando um quadro estoura o orçamento.
+        let color = if i == 1 && gs.frame_times.over_budget {
+            Color::RED
+        } else {
+            Color::from([0.6, 1.0, 0.6, 1.0])
+        };
+        text.draw(canvas, origin + Vec2::new(BLOCK_SIZE, i as f32 * 16.0), color);
+    }
+    Ok(())
+}
+
+/// Console de depuração: respostas recentes e a linha sendo digitada, numa
+/// faixa escura no pé da tela.
+fn draw_console(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    const LINE_HEIGHT: f32 = 18.0;
+    let (screen_w, screen_h) = ui_size(ctx, &gs.config);
+    let prompt = format!("> {}_", gs.console.input);
+    let lines: Vec<&str> = gs.console.output.iter().map(String::as_str).chain([prompt.as_str()]).collect();
+    let padding = gs.config.hud_padding;
+    let height = lines.len() as f32 * LINE_HEIGHT + padding;
+    let backdrop = Mesh::new_rectangle(
+        ctx,
+        DrawMode::fill(),
+        Rect::new(0.0, screen_h - height, screen_w, height),
+        Color::from([0.0, 0.0, 0.0, 0.75]),
+    )?;
+    canvas.draw(&backdrop, DrawParam::new());
+    for (i, line) in lines.iter().enumerate() {
+        let text = CrispText::new(ctx, canvas, *line, DEBUG_FONT, 16.0);
+        let pos = Vec2::new(padding, screen_h - height + padding / 2.0 + i as f32 * LINE_HEIGHT);
+        text.draw(canvas, pos, Color::WHITE);
+    }
+    Ok(())
+}
+
+fn draw_centered_text(
+    canvas: &mut graphics::Canvas,
+    ctx: &mut Context,
+    text_str: &str,
+    size: f32,
+    y_offset: f32,
+    color: Color,
+    shadow: Option<Color>,
+) -> GameResult {
+    // O canvas já está nas unidades da interface; o centro vem delas.
+    let screen = canvas.screen_coordinates().unwrap_or_default();
+    let (screen_w, screen_h) = (screen.w, screen.h);
+    let text = CrispText::new(ctx, canvas, text_str, MAIN_FONT, size);
+
+    if let Some(text_rect) = text.dimensions(ctx) {
+        let text_w = text_rect.w;
+        let pos = Vec2::new((screen_w - text_w) / 2.0, screen_h / 2.0 + y_offset);
+        draw_text(canvas, &text, pos, color, shadow);
+    }
+    Ok(())
+}
+
+/// Um código de desafio centralizado como `draw_centered_text`. Ele é
+/// comprido demais para a fonte principal; a monoespaçada cabe na janela e
+/// não confunde letras parecidas.
+fn draw_centered_code(
+    canvas: &mut graphics::Canvas,
+    ctx: &mut Context,
+    code: &str,
+    y_offset: f32,
+    color: Color,
+    shadow: Option<Color>,
+) -> GameResult {
+    let screen = canvas.screen_coordinates().unwrap_or_default();
+    let text = CrispText::new(ctx, canvas, code, DEBUG_FONT, 16.0);
+    if let Some(rect) = text.dimensions(ctx) {
+        let pos = Vec2::new((screen.w - rect.w) / 2.0, screen.h / 2.0 + y_offset);
+        draw_text(canvas, &text, pos, color, shadow);
+    }
+    Ok(())
+}
+
+/// Desenha o texto em `pos`, com uma cópia deslocada na cor da sombra por baixo
+/// quando o tema pede sombra.
+fn draw_text(canvas: &mut graphics::Canvas, text: &CrispText, pos: Vec2, color: Color, shadow: Option<Color>) {
+    if let Some(shadow) = shadow {
+        text.draw(canvas, pos + Vec2::splat(TEXT_SHADOW_OFFSET), shadow);
+    }
+    text.draw(canvas, pos, color);
+}
+
+fn draw_menu(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    // CORRIGIDO: A fonte não é mais passada como argumento.
+    draw_centered_text(
+        canvas,
+        ctx,
+        "Rusted Snake",
+        64.0,
+        -100.0,
+        Color::from([0.8, 0.4, 0.1, 1.0]),
+        gs.theme().text_shadow,
+    )?;
+    for (i, item) in MENU_ITEMS.iter().enumerate() {
+        let color = if i == gs.menu_selection {
+            Color::from([0.9, 0.5, 0.2, 1.0])
+        } else {
+            Color::WHITE
+        };
+        draw_centered_text(canvas, ctx, item.label(), 28.0, -30.0 + i as f32 * 32.0, color, gs.theme().text_shadow)?;
+    }
+    if let Some((cheat, time_left)) = gs.cheat_flash {
+        let alpha = (time_left / CHEAT_FLASH_DURATION).min(1.0);
+        let color = Color::new(0.6, 1.0, 0.6, alpha);
+        draw_centered_text(canvas, ctx, cheat.message(), 20.0, 280.0, color, gs.theme().text_shadow)?;
+    }
+    Ok(())
+}
+
+fn draw_leaderboard(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    draw_centered_text(
+        canvas,
+        ctx,
+        "Placar",
+        48.0,
+        -200.0,
+        Color::from([0.8, 0.4, 0.1, 1.0]),
+        gs.theme().text_shadow,
+    )?;
+    if gs.leaderboard.entries.is_empty() {
+        draw_centered_text(canvas, ctx, "Nenhuma partida registrada", 24.0, 0.0, Color::WHITE, gs.theme().text_shadow)?;
+    }
+    for (i, entry) in gs.leaderboard.entries.iter().enumerate() {
+        let marker = if entry.handicapped { " *" } else { "" };
+        draw_centered_text(
+            canvas,
+            ctx,
+            &format!("{}. {}{}", i + 1, entry.score, marker),
+            24.0,
+            -120.0 + i as f32 * 30.0,
+            Color::WHITE,
+            gs.theme().text_shadow,
+        )?;
+    }
+    let today = daily::today();
+    if let Some(best) = gs.leaderboard.daily.get(&today) {
+        draw_centered_text(
+            canvas,
+            ctx,
+            &format!("Desafio de {}: {}", daily::date_label(today), best),
+            22.0,
+            170.0,
+            Color::from([0.9, 0.5, 0.2, 1.0]),
+            gs.theme().text_shadow,
+        )?;
+    }
+    let (footer, color) = if gs.confirm_reset {
+        ("Zerar o placar e a maior pontuação? S confirma, outra tecla cancela", Color::from([1.0, 0.4, 0.3, 1.0]))
+    } else {
+        ("* partida com handicap   -   R para zerar   -   ESC para voltar", Color::from([0.7, 0.7, 0.7, 1.0]))
+    };
+    draw_centered_text(canvas, ctx, footer, 18.0, 200.0, color, gs.theme().text_shadow)?;
+    Ok(())
+}
+
+fn draw_stats(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    draw_centered_text(
+        canvas,
+        ctx,
+        "Estatísticas",
+        48.0,
+        -200.0,
+        Color::from([0.8, 0.4, 0.1, 1.0]),
+        gs.theme().text_shadow,
+    )?;
+    let stats = &gs.stats;
+    let minutes = (stats.play_time / 60.0) as u32;
+    let mut lines = vec![
+        format!("Partidas: {}", stats.games_played),
+        format!("Maçãs comidas: {}", stats.food_eaten),
+        format!("Maior pontuação: {}", stats.best_score),
+        format!("Tempo de jogo: {}h {:02}min", minutes / 60, minutes % 60),
+    ];
+    for cause in DeathCause::ALL {
+        lines.push(format!("{}: {}", cause.message(), stats.deaths.get(&cause).unwrap_or(&0)));
+    }
+    for (i, line) in lines.iter().enumerate() {
+        draw_centered_text(canvas, ctx, line, 22.0, -130.0 + i as f32 * 30.0, Color::WHITE, gs.theme().text_shadow)?;
+    }
+    draw_centered_text(
+        canvas,
+        ctx,
+        "ESC para voltar",
+        18.0,
+        210.0,
+        Color::from([0.7, 0.7, 0.7, 1.0]),
+        gs.theme().text_shadow,
+    )?;
+    Ok(())
+}
+
+fn draw_help(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    draw_centered_text(
+        canvas,
+        ctx,
+        "Ajuda",
+        48.0,
+        -200.0,
+        Color::from([0.8, 0.4, 0.1, 1.0]),
+        gs.theme().text_shadow,
+    )?;
+    let lines = [
+        "Setas ou WASD: mover a cobra",
+        "Coma as maçãs para crescer e pontuar",
+        "Não bata nas paredes nem no próprio corpo",
+        "Maçãs douradas valem 5 pontos; as roxas encolhem a cobra",
+        "Não encoste nas cobras verdes inimigas",
+        "Z: liga/desliga a câmera lenta",
+        "Shift: segure e solte para avançar duas casas",
+        "+ / -: aumenta ou diminui as células",
+        "F3: informações de depuração",
+        "Ctrl+Q: sair do jogo",
+    ];
+    for (i, line) in lines.iter().enumerate() {
+        draw_centered_text(canvas, ctx, line, 22.0, -130.0 + i as f32 * 28.0, Color::WHITE, gs.theme().text_shadow)?;
+    }
+    draw_centered_text(
+        canvas,
+        ctx,
+        "ESC para voltar",
+        18.0,
+        200.0,
+        Color::from([0.7, 0.7, 0.7, 1.0]),
+        gs.theme().text_shadow,
+    )?;
+    Ok(())
+}
+
+fn draw_settings(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    draw_centered_text(
+        canvas,
+        ctx,
+        "Configurações",
+        48.0,
+        -150.0,
+        Color::from([0.8, 0.4, 0.1, 1.0]),
+        gs.theme().text_shadow,
+    )?;
+    // A lista rola para manter a opção selecionada sempre visível.
+    let visible = SETTINGS_VISIBLE.min(SETTINGS.len());
+    let first = gs
+        .settings_selection
+        .saturating_sub(visible / 2)
+        .min(SETTINGS.len() - visible);
+    for (row, (i, setting)) in SETTINGS.iter().enumerate().skip(first).take(visible).enumerate() {
+        let color = if i == gs.settings_selection {
+            Color::from([0.9, 0.5, 0.2, 1.0])
+        } else {
+            Color::WHITE
+        };
+        draw_centered_text(
+            canvas,
+            ctx,
+            &setting.label(&gs.config),
+            24.0,
+            -80.0 + row as f32 * 36.0,
+            color,
+            gs.theme().text_shadow,
+        )?;
+    }
+    draw_centered_text(
+        canvas,
+        ctx,
+        "ESC para voltar",
+        18.0,
+        200.0,
+        Color::from([0.7, 0.7, 0.7, 1.0]),
+        gs.theme().text_shadow,
+    )?;
     Ok(())
 }
 
-fn draw_game_over(_gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+fn draw_game_over(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
     // CORRIGIDO: A fonte não é mais passada como argumento.
     draw_centered_text(
         canvas,
@@ -424,15 +3549,164 @@ fn draw_game_over(_gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Can
         48.0,
         -50.0,
         Color::RED,
+        gs.theme().text_shadow,
     )?;
+    if let Some(cause) = gs.death_cause {
+        draw_centered_text(
+            canvas,
+            ctx,
+            cause.message(),
+            24.0,
+            10.0,
+            Color::from([0.9, 0.5, 0.2, 1.0]),
+            gs.theme().text_shadow,
+        )?;
+    }
+    let mut prompt = "Pressione qualquer tecla para voltar ao menu".to_string();
+    if gs.config.auto_return_delay > 0 {
+        let secs_left = (gs.config.auto_return_delay as f32 - gs.game_over_timer).ceil().max(0.0);
+        prompt += &format!(" ({:.0}s)", secs_left);
+    }
     draw_centered_text(
         canvas,
         ctx,
-        "Pressione qualquer tecla para voltar ao menu",
+        &prompt,
         24.0,
-        0.0,
+        50.0,
+        Color::WHITE,
+        gs.theme().text_shadow,
+    )?;
+    draw_score_history(gs, ctx, canvas)?;
+    let gray = Color::from([0.7, 0.7, 0.7, 1.0]);
+    draw_centered_text(canvas, ctx, "Código do desafio:", 18.0, 190.0, gray, gs.theme().text_shadow)?;
+    let code = Challenge::from_config(gs.seed, (gs.grid_width, gs.grid_height), &gs.config).encode();
+    draw_centered_code(canvas, ctx, &code, 216.0, gray, gs.theme().text_shadow)
+}
+
+/// As duas arenas da corrida, lado a lado, com a pontuação de cada jogador e
+/// o resultado quando a corrida acaba.
+fn draw_split_screen(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    let Some(race) = &gs.race else {
+        return Ok(());
+    };
+    let theme = gs.theme();
+    let cell = Rect::new(0.0, 0.0, BLOCK_SIZE, BLOCK_SIZE);
+    let wall_mesh = Mesh::new_rectangle(ctx, DrawMode::fill(), cell, theme.wall)?;
+    let block_mesh = Mesh::new_rectangle(
+        ctx,
+        DrawMode::fill(),
+        Rect::new(2.0, 2.0, BLOCK_SIZE - 4.0, BLOCK_SIZE - 4.0),
         Color::WHITE,
     )?;
+
+    for (i, board) in race.iter().enumerate() {
+        let origin = Vec2::new(i as f32 * board.width as f32 * BLOCK_SIZE, 0.0);
+        for y in 0..board.height {
+            for x in 0..board.width {
+                let pos = GridPos::new(x, y);
+                if board.is_wall(pos) {
+                    canvas.draw(&wall_mesh, origin + cell_to_pixel(pos));
+                }
+            }
+        }
+        canvas.draw(&block_mesh, DrawParam::new().dest(origin + cell_to_pixel(board.food)).color(theme.food));
+        // Uma cobra que morreu fica apagada até o fim da corrida.
+        let alpha = if board.alive { 1.0 } else { 0.35 };
+        for (j, block) in board.snake.body.iter().enumerate() {
+            let color = if j == 0 { theme.snake_head } else { theme.snake_body };
+            let color = Color::new(color.r, color.g, color.b, alpha);
+            canvas.draw(&block_mesh, DrawParam::new().dest(origin + cell_to_pixel(*block)).color(color));
+        }
+
+        let label = format!("Jogador {}: {}/{}", i + 1, board.score, board::TARGET_SCORE);
+        let score = CrispText::new(ctx, canvas, label, MAIN_FONT, 16.0);
+        let pos = origin + Vec2::new(BLOCK_SIZE + gs.config.hud_padding, BLOCK_SIZE + gs.config.hud_padding);
+        draw_text(canvas, &score, pos, Color::WHITE, theme.text_shadow);
+    }
+
+    if let Some(result) = board::race_result(race) {
+        let message = match result {
+            RaceResult::Winner(i) => format!("Jogador {} venceu!", i + 1),
+            RaceResult::Draw => "Empate!".to_string(),
+        };
+        draw_centered_text(canvas, ctx, &message, 48.0, -50.0, Color::from([0.9, 0.5, 0.2, 1.0]), theme.text_shadow)?;
+        draw_centered_text(
+            canvas,
+            ctx,
+            "Pressione qualquer tecla para voltar ao menu",
+            24.0,
+            20.0,
+            Color::WHITE,
+            theme.text_shadow,
+        )?;
+    }
+    Ok(())
+}
+
+fn draw_import_challenge(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    draw_centered_text(
+        canvas,
+        ctx,
+        "Importar desafio",
+        48.0,
+        -150.0,
+        Color::from([0.8, 0.4, 0.1, 1.0]),
+        gs.theme().text_shadow,
+    )?;
+    draw_centered_text(canvas, ctx, "Digite o código:", 22.0, -50.0, Color::WHITE, gs.theme().text_shadow)?;
+    let field = format!("{}_", gs.challenge_input);
+    draw_centered_code(canvas, ctx, &field, 0.0, Color::from([0.9, 0.5, 0.2, 1.0]), gs.theme().text_shadow)?;
+    if gs.challenge_error {
+        draw_centered_text(canvas, ctx, "Código inválido", 20.0, 50.0, Color::RED, gs.theme().text_shadow)?;
+    }
+    draw_centered_text(
+        canvas,
+        ctx,
+        "Enter para jogar, ESC para voltar",
+        18.0,
+        200.0,
+        Color::from([0.7, 0.7, 0.7, 1.0]),
+        gs.theme().text_shadow,
+    )
+}
+
+/// Gráfico em degraus da pontuação ao longo da partida, abaixo das mensagens.
+fn draw_score_history(gs: &GameState, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+    let (screen_w, screen_h) = ui_size(ctx, &gs.config);
+    let plot = Rect::new(screen_w / 2.0 - 120.0, screen_h / 2.0 + 90.0, 240.0, 80.0);
+    let max_time = gs.run_time.max(1.0);
+    let max_score = gs.score_history.iter().map(|&(_, s)| s).max().unwrap_or(0).max(1) as f32;
+    let to_screen = |time: f32, score: u32| {
+        Vec2::new(
+            plot.x + time / max_time * plot.w,
+            plot.bottom() - score as f32 / max_score * plot.h,
+        )
+    };
+
+    let mut points = Vec::with_capacity(gs.score_history.len() * 2 + 1);
+    for (i, &(time, score)) in gs.score_history.iter().enumerate() {
+        if i > 0 {
+            points.push(to_screen(time, gs.score_history[i - 1].1));
+        }
+        points.push(to_screen(time, score));
+    }
+    if let Some(&(_, score)) = gs.score_history.last() {
+        points.push(to_screen(gs.run_time, score));
+    }
+    // Uma linha precisa de pelo menos dois pontos distintos.
+    if points.len() < 2 || points[0] == points[points.len() - 1] {
+        return Ok(());
+    }
+
+    let axes = Mesh::new_line(
+        ctx,
+        &[Vec2::new(plot.x, plot.y), Vec2::new(plot.x, plot.bottom()), Vec2::new(plot.right(), plot.bottom())],
+        1.0,
+        Color::from([0.4, 0.4, 0.4, 1.0]),
+    )?;
+    canvas.draw(&axes, DrawParam::new());
+    let line = Mesh::new_line(ctx, &points, 2.0, Color::from([0.9, 0.5, 0.2, 1.0]))?;
+    canvas.draw(&line, DrawParam::new());
     Ok(())
 }
 
@@ -446,13 +3720,350 @@ pub fn main() -> GameResult {
         path::PathBuf::from("./assets")
     };
 
-    let (mut ctx, event_loop) = ContextBuilder::new("snake_rust", "Gemini")
-        .window_setup(WindowSetup::default().title("Rusted Snake"))
-        .window_mode(WindowMode::default().dimensions(816.0, 600.0).resizable(true))
-        .add_resource_path(resource_dir)
-        .build()?;
+    // A configuração é lida antes da janela existir, porque o antisserrilhado
+    // precisa ser escolhido na criação dela.
+    let config = Config::load(&Filesystem::new(GAME_ID, AUTHOR, "resources", "resources.zip")?);
+    if let Some(runs) = benchmark::requested_runs() {
+        benchmark::run(config, runs);
+        return Ok(());
+    }
+    let build = |samples: NumSamples| {
+        ContextBuilder::new(GAME_ID, AUTHOR)
+            .window_setup(WindowSetup::default().title("Rusted Snake").samples(samples))
+            .window_mode(config.window_mode())
+            .add_resource_path(&resource_dir)
+            .build()
+    };
+    // Se a placa de vídeo recusar as amostras pedidas, abre sem antisserrilhado.
+    let (mut ctx, event_loop) = match build(config.num_samples()) {
+        Ok(built) => built,
+        Err(e) if config.num_samples() != NumSamples::One => {
+            eprintln!("Antisserrilhado indisponível ({}); usando 1 amostra.", e);
+            build(NumSamples::One)?
+        }
+        Err(e) => return Err(e),
+    };
 
     // A criação de GameState agora pode falhar, então usamos `?`.
-    let state = GameState::new(&mut ctx)?;
+    let state = GameState::new(&mut ctx, config)?;
     event::run(ctx, event_loop, state)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: u64 = 7;
+
+    /// Uma partida começada com a semente fixa e sem janela.
+    fn start(config: Config, width: i32, height: i32) -> GameState {
+        let mut gs = GameState::from_parts(config, Leaderboard::default(), width, height, SEED);
+        gs.start_run(SEED, None);
+        gs
+    }
+
+    #[test]
+    fn scripted_run_eats_and_then_hits_the_wall() {
+        let config = Config {
+            food_script: vec![GridPos::new(5, 2), GridPos::new(5, 8)],
+            ..Config::default()
+        };
+        let mut gs = start(config, 12, 10);
+        assert_eq!(gs.food, GridPos::new(5, 2));
+
+        gs.step();
+        gs.step();
+        assert_eq!(gs.score, 1);
+        assert_eq!(gs.snake.body.len(), 4);
+        assert_eq!(gs.food, GridPos::new(5, 8));
+
+        let mut steps = 0;
+        while matches!(gs.mode, GameMode::Playing) && steps < 20 {
+            gs.step();
+            steps += 1;
+        }
+        assert!(matches!(gs.mode, GameMode::DeathReplay));
+        assert_eq!(gs.death_cause, Some(DeathCause::Wall));
+        assert_eq!(gs.snake.head(), GridPos::new(11, 2));
+        assert_eq!(gs.score, 1);
+    }
+
+    #[test]
+    fn opposite_turns_in_one_frame_cancel_out() {
+        let mut gs = start(Config::default(), 12, 12);
+        gs.queue_direction(Direction::Down);
+        gs.step();
+        let head = gs.snake.head();
+
+        // Esquerda e direita antes do mesmo passo, descendo: seguir reto.
+        gs.queue_direction(Direction::Left);
+        gs.queue_direction(Direction::Right);
+        assert!(gs.input_queue.is_empty());
+        gs.step();
+        assert!(matches!(gs.mode, GameMode::Playing));
+        assert_eq!(gs.snake.direction, Direction::Down);
+        assert_eq!(gs.snake.head(), head.neighbor(Direction::Down));
+    }
+
+    #[test]
+    fn cancelled_wall_turn_does_not_allow_reversing_into_the_neck() {
+        let config = Config { wall_bounce: true, ..Config::default() };
+        let mut gs = start(config, 12, 12);
+        gs.queue_direction(Direction::Up);
+        gs.step();
+        gs.queue_direction(Direction::Right);
+        gs.step();
+        let head = gs.snake.head();
+        assert_eq!(head.y, 1);
+
+        // A virada para a parede é cancelada: a cobra fica parada, ainda
+        // tendo andado para a direita por último.
+        gs.queue_direction(Direction::Up);
+        gs.step();
+        assert_eq!(gs.snake.head(), head);
+        assert_eq!(gs.snake.last_moved, Direction::Right);
+
+        // Voltar para a esquerda seria entrar no pescoço.
+        gs.queue_direction(Direction::Left);
+        assert!(gs.input_queue.is_empty());
+        gs.step();
+        assert!(matches!(gs.mode, GameMode::Playing));
+        assert_eq!(gs.snake.head(), head);
+    }
+
+    /// Corre para a direita até a maçã do roteiro, e o anel da arena se fecha
+    /// sobre ela um passo antes de a cabeça chegar.
+    fn eat_under_the_ring(lethal_meal: LethalMealPolicy) -> GameState {
+        let config = Config {
+            lethal_meal,
+            shrinking_arena: true,
+            food_script: vec![GridPos::new(10, 2)],
+            ..Config::default()
+        };
+        let mut gs = start(config, 12, 10);
+        for _ in 0..6 {
+            gs.step();
+        }
+        assert!(gs.shrink_arena());
+        assert!(gs.is_wall(gs.food));
+        gs.step();
+        assert_eq!(gs.death_cause, Some(DeathCause::Obstacle));
+        assert_eq!(gs.snake.head(), GridPos::new(10, 2));
+        gs
+    }
+
+    #[test]
+    fn lethal_meal_score_then_die_counts_the_food() {
+        assert_eq!(eat_under_the_ring(LethalMealPolicy::ScoreThenDie).score, 1);
+    }
+
+    #[test]
+    fn lethal_meal_die_without_score_ignores_the_food() {
+        let gs = eat_under_the_ring(LethalMealPolicy::DieWithoutScore);
+        assert_eq!(gs.score, 0);
+        assert_eq!(gs.food, GridPos::new(10, 2));
+    }
+
+    #[test]
+    fn closing_ring_removes_the_special_food_and_pads_under_it() {
+        let mut gs = start(Config { speed_pads: true, ..Config::default() }, 12, 10);
+        gs.special_food = Some(SpecialFood { kind: SpecialFoodKind::Bonus, pos: GridPos::new(1, 5), ttl: 5.0 });
+        gs.speed_pads = vec![
+            SpeedPad { kind: PadKind::Fast, pos: GridPos::new(10, 4) },
+            SpeedPad { kind: PadKind::Slow, pos: GridPos::new(5, 5) },
+        ];
+        assert!(gs.shrink_arena());
+        assert!(gs.special_food.is_none());
+        assert_eq!(gs.speed_pads.len(), 1);
+        assert_eq!(gs.speed_pads[0].pos, GridPos::new(5, 5));
+    }
+
+    #[test]
+    fn solid_border_is_lethal_on_the_outer_ring() {
+        let gs = start(Config::default(), 12, 10);
+        for (x, y) in [(0, 5), (11, 5), (5, 0), (5, 9)] {
+            assert!(gs.is_out_of_bounds(GridPos::new(x, y)), "({x}, {y})");
+        }
+        for (x, y) in [(1, 5), (10, 5), (5, 1), (5, 8)] {
+            assert!(!gs.is_out_of_bounds(GridPos::new(x, y)), "({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn passable_border_is_lethal_only_outside_the_grid() {
+        let gs = start(Config { passable_border: true, ..Config::default() }, 12, 10);
+        for (x, y) in [(-1, 5), (12, 5), (5, -1), (5, 10)] {
+            assert!(gs.is_out_of_bounds(GridPos::new(x, y)), "({x}, {y})");
+        }
+        for (x, y) in [(0, 5), (11, 5), (5, 0), (5, 9)] {
+            assert!(!gs.is_out_of_bounds(GridPos::new(x, y)), "({x}, {y})");
+        }
+    }
+
+    /// Anda da coluna 3 até atravessar a borda direita e sair na coluna 1.
+    fn wrap_around_the_right_edge(gs: &mut GameState) {
+        for _ in 0..8 {
+            if !matches!(gs.mode, GameMode::Playing) {
+                return;
+            }
+            gs.step();
+        }
+    }
+
+    #[test]
+    fn wrapping_into_free_space_survives() {
+        let mut gs = start(Config { wrap_edges: true, ..Config::default() }, 12, 10);
+        wrap_around_the_right_edge(&mut gs);
+        assert!(matches!(gs.mode, GameMode::Playing));
+        assert_eq!(gs.snake.head(), GridPos::new(1, 2));
+    }
+
+    #[test]
+    fn wrapping_into_an_obstacle_is_lethal() {
+        let mut gs = start(Config { wrap_edges: true, ..Config::default() }, 12, 10);
+        gs.obstacles.push(GridPos::new(1, 2));
+        wrap_around_the_right_edge(&mut gs);
+        assert!(matches!(gs.mode, GameMode::DeathReplay));
+        assert_eq!(gs.death_cause, Some(DeathCause::Obstacle));
+        assert_eq!(gs.snake.head(), GridPos::new(1, 2));
+    }
+
+    #[test]
+    fn wrap_selection_wraps_at_both_ends() {
+        assert_eq!(wrap_selection(0, -1, 4), 3);
+        assert_eq!(wrap_selection(3, 1, 4), 0);
+        assert_eq!(wrap_selection(1, 1, 4), 2);
+        assert_eq!(wrap_selection(0, -1, 0), 0);
+        assert_eq!(wrap_selection(0, 1, 0), 0);
+    }
+
+    #[test]
+    fn movement_clock_carries_the_remainder_and_caps_stalls() {
+        let mut gs = start(Config::default(), 12, 10);
+        gs.speed_override = Some(0.125);
+        // O travamento de 1s rende um passo só, com um intervalo inteiro
+        // guardado para o quadro seguinte.
+        let deltas = [0.0625, 0.03125, 0.0625, 0.125, 0.03125, 1.0, 0.0, 0.0625];
+        let ticks: Vec<bool> = deltas.iter().map(|&dt| gs.advance_clock(dt)).collect();
+        assert_eq!(ticks, [false, false, true, true, false, true, true, false]);
+        assert_eq!(gs.time_since_last_update, 0.0625);
+    }
+
+    #[test]
+    fn instant_turns_never_bunch_steps_together() {
+        let mut gs = start(Config { instant_turns: true, ..Config::default() }, 40, 40);
+        gs.intro_timer = 0.0;
+        let interval = 0.125;
+        gs.speed_override = Some(interval);
+        let dt = 1.0 / 64.0;
+
+        // Escadinha para baixo e para a direita, virando a cada cinco quadros.
+        let mut step_times = Vec::new();
+        for frame in 0..100 {
+            let head = gs.snake.head();
+            if frame % 5 == 0 {
+                let turn = if gs.snake.direction == Direction::Right { Direction::Down } else { Direction::Right };
+                gs.queue_direction(turn);
+            }
+            if gs.advance_clock(dt) {
+                gs.step();
+            }
+            if gs.snake.head() != head {
+                step_times.push(frame as f32 * dt);
+            }
+        }
+
+        assert!(matches!(gs.mode, GameMode::Playing));
+        let gaps: Vec<f32> = step_times.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(gaps.iter().any(|&gap| gap < interval), "nenhuma virada andou na hora");
+        for gap in gaps {
+            assert!(gap >= interval * INSTANT_TURN_THRESHOLD, "passos a {gap}s um do outro");
+        }
+    }
+
+    #[test]
+    fn lives_are_spent_on_the_border_only() {
+        let config = Config { wall_lives: 1, ..Config::default() };
+        let mut gs = start(config.clone(), 12, 10);
+        for _ in 0..8 {
+            gs.step();
+        }
+        assert!(matches!(gs.mode, GameMode::Playing));
+        assert_eq!(gs.lives, 0);
+        assert_eq!(gs.snake.head(), GridPos::new(6, 5));
+
+        let mut gs = start(config, 12, 10);
+        gs.obstacles.push(GridPos::new(6, 2));
+        for _ in 0..3 {
+            gs.step();
+        }
+        assert_eq!(gs.death_cause, Some(DeathCause::Obstacle));
+        assert_eq!(gs.lives, 1);
+    }
+
+    #[test]
+    fn menu_preview_keeps_the_finished_snake() {
+        let mut gs = start(Config { menu_preview: true, ..Config::default() }, 12, 10);
+        for _ in 0..4 {
+            gs.step();
+        }
+        let body = gs.snake.body.clone();
+        gs.show_menu();
+        assert!(matches!(gs.mode, GameMode::Menu));
+        assert_eq!(gs.snake.body, body);
+        assert!(!gs.snake.occupies(gs.food));
+    }
+
+    #[test]
+    fn console_grow_stops_at_max_length() {
+        let mut gs = start(Config { max_length: Some(5), ..Config::default() }, 12, 10);
+        gs.apply_command(Command::Grow(10));
+        assert_eq!(gs.snake.body.len(), 5);
+    }
+
+    #[test]
+    fn daily_run_ignores_the_player_config_and_window() {
+        let day = 20_000;
+        let tweaked = Config {
+            maze_density: 0.2,
+            enemy_count: 2,
+            starting_score: 10,
+            food_center_bias: 3.0,
+            food_script: vec![GridPos::new(4, 2)],
+            ..Config::default()
+        };
+        let runs = [(Config::default(), 12, 10), (tweaked, 50, 40)].map(|(config, width, height)| {
+            let mut gs = GameState::from_parts(config, Leaderboard::default(), width, height, SEED);
+            daily::challenge(day).apply(&mut gs.config);
+            (gs.grid_width, gs.grid_height) = gs.config.grid_size(0.0, 0.0, BLOCK_SIZE);
+            gs.start_run(daily::seed(day), Some(day));
+            let mut foods = vec![gs.food];
+            for turn in [Direction::Down, Direction::Right, Direction::Down, Direction::Right] {
+                gs.queue_direction(turn);
+                for _ in 0..3 {
+                    gs.step();
+                    foods.push(gs.food);
+                }
+            }
+            assert_eq!((gs.grid_width, gs.grid_height), daily::GRID);
+            (foods, gs.score, gs.obstacles.len(), gs.enemies.len())
+        });
+        assert_eq!(runs[0], runs[1]);
+    }
+
+    #[test]
+    fn starting_to_the_left_spawns_facing_left() {
+        let mut gs = GameState::from_parts(Config::default(), Leaderboard::default(), 12, 10, SEED);
+        gs.restart_facing(Direction::Left);
+        assert_eq!(gs.snake.direction, Direction::Left);
+        assert_eq!(gs.snake.head(), GridPos::new(8, 2));
+        gs.step();
+        assert!(matches!(gs.mode, GameMode::Playing));
+        assert_eq!(gs.snake.head(), GridPos::new(7, 2));
+
+        // A largada seguinte volta ao normal.
+        gs.restart();
+        assert_eq!(gs.snake.direction, Direction::Right);
+        assert_eq!(gs.snake.head(), SNAKE_START);
+    }
+}
@@ -0,0 +1,60 @@
+use ggez::audio::{SoundSource, Source};
+use ggez::{Context, GameResult};
+
+const MENU_TRACK_PATH: &str = "/music_menu.ogg";
+const GAME_TRACK_PATH: &str = "/music_game.ogg";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Track {
+    Menu,
+    Game,
+}
+
+/// Músicas de fundo em loop. Arquivos ausentes simplesmente não tocam.
+#[derive(Default)]
+pub struct Music {
+    menu: Option<Source>,
+    game: Option<Source>,
+    current: Option<Track>,
+}
+
+fn load_track(ctx: &mut Context, path: &str) -> Option<Source> {
+    let mut source = Source::new(ctx, path).ok()?;
+    source.set_repeat(true);
+    Some(source)
+}
+
+impl Music {
+    pub fn load(ctx: &mut Context) -> Music {
+        Music {
+            menu: load_track(ctx, MENU_TRACK_PATH),
+            game: load_track(ctx, GAME_TRACK_PATH),
+            current: None,
+        }
+    }
+
+    fn source(&mut self, track: Track) -> Option<&mut Source> {
+        match track {
+            Track::Menu => self.menu.as_mut(),
+            Track::Game => self.game.as_mut(),
+        }
+    }
+
+    /// Troca para a faixa pedida (ou silêncio, com `None`) e aplica o volume.
+    /// Chamado a cada quadro; só reinicia a música quando a faixa muda.
+    pub fn update(&mut self, ctx: &Context, track: Option<Track>, volume: f32) -> GameResult {
+        if self.current != track {
+            if let Some(source) = self.current.and_then(|t| self.source(t)) {
+                source.stop(ctx)?;
+            }
+            if let Some(source) = track.and_then(|t| self.source(t)) {
+                source.play(ctx)?;
+            }
+            self.current = track;
+        }
+        if let Some(source) = self.current.and_then(|t| self.source(t)) {
+            source.set_volume(volume);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+
+use ggez::glam::Vec2;
+use ggez::graphics::Color;
+use rand::Rng;
+
+/// Um fragmento que voa em linha reta e some aos poucos.
+pub struct Particle {
+    pub pos: Vec2,
+    velocity: Vec2,
+    pub color: Color,
+    /// Segundos restantes de vida, de `lifetime` até zero.
+    life: f32,
+    lifetime: f32,
+}
+
+impl Particle {
+    /// Opacidade atual: começa opaca e chega a zero no fim da vida.
+    pub fn alpha(&self) -> f32 {
+        (self.life / self.lifetime).clamp(0.0, 1.0)
+    }
+}
+
+/// Efeitos puramente visuais; não usa o gerador da partida, para não
+/// alterar a sequência de sorteios do jogo.
+pub struct Particles {
+    pub particles: VecDeque<Particle>,
+    /// Máximo de partículas vivas ao mesmo tempo. Passando disso, as mais
+    /// antigas dão lugar às novas.
+    pub capacity: usize,
+}
+
+impl Particles {
+    pub fn new(capacity: usize) -> Particles {
+        Particles {
+            particles: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Espalha `count` partículas a partir de `center`, em direções aleatórias.
+    pub fn burst(&mut self, center: Vec2, color: Color, count: usize, speed: f32, lifetime: f32) {
+        let mut rng = rand::rng();
+        for _ in 0..count {
+            let angle = rng.random_range(0.0..std::f32::consts::TAU);
+            let speed = speed * rng.random_range(0.5..1.0);
+            let lifetime = lifetime * rng.random_range(0.7..1.0);
+            if self.particles.len() >= self.capacity {
+                self.particles.pop_front();
+            }
+            self.particles.push_back(Particle {
+                pos: center,
+                velocity: Vec2::from_angle(angle) * speed,
+                color,
+                life: lifetime,
+                lifetime,
+            });
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for particle in &mut self.particles {
+            particle.pos += particle.velocity * dt;
+            particle.life -= dt;
+        }
+        self.particles.retain(|p| p.life > 0.0);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn burst_past_capacity_evicts_the_oldest() {
+        let (old, new) = (Vec2::new(1.0, 1.0), Vec2::new(9.0, 9.0));
+        let mut particles = Particles::new(5);
+        particles.burst(old, Color::WHITE, 3, 10.0, 1.0);
+        particles.burst(new, Color::WHITE, 4, 10.0, 1.0);
+
+        assert_eq!(particles.particles.len(), 5);
+        let positions: Vec<Vec2> = particles.particles.iter().map(|p| p.pos).collect();
+        assert_eq!(positions, [old, new, new, new, new]);
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ggez::graphics::ImageEncodingFormat;
+use ggez::{Context, GameResult};
+use serde::Serialize;
+
+// Cada gravação ganha uma pasta própria aqui, no diretório do usuário.
+const RECORDINGS_DIR: &str = "/recordings";
+/// Quadros salvos por segundo; os quadros entre duas capturas são pulados.
+pub const CAPTURE_FPS: f32 = 15.0;
+
+/// Descrição da gravação salva em `manifest.json`, para montar um GIF com o
+/// ritmo certo em outro programa.
+#[derive(Serialize)]
+struct Manifest<'a> {
+    fps: f32,
+    frames: &'a [Frame],
+}
+
+#[derive(Serialize)]
+struct Frame {
+    file: String,
+    /// Segundos desde o começo da gravação.
+    time: f32,
+}
+
+/// Gravação em andamento: cada captura vira um PNG numerado na pasta.
+pub struct Recording {
+    dir: String,
+    elapsed: f32,
+    next_capture: f32,
+    frames: Vec<Frame>,
+}
+
+impl Recording {
+    /// Cria a pasta da gravação, com o nome tirado do relógio.
+    pub fn start(ctx: &Context) -> GameResult<Recording> {
+        let stamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let dir = format!("{}/{}", RECORDINGS_DIR, stamp);
+        ctx.fs.create_dir(&dir)?;
+        Ok(Recording {
+            dir,
+            elapsed: 0.0,
+            next_capture: 0.0,
+            frames: Vec::new(),
+        })
+    }
+
+    pub fn dir(&self) -> &str {
+        &self.dir
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Conta `dt` segundos e salva o quadro atual da tela, se já for a hora
+    /// da próxima captura. Deve ser chamada depois de o quadro ser desenhado.
+    pub fn capture(&mut self, ctx: &Context, dt: f32) -> GameResult {
+        self.elapsed += dt;
+        if self.elapsed < self.next_capture {
+            return Ok(());
+        }
+        self.next_capture += 1.0 / CAPTURE_FPS;
+        // Depois de um travamento, não tenta recuperar as capturas perdidas.
+        self.next_capture = self.next_capture.max(self.elapsed);
+        let file = format!("frame_{:05}.png", self.frames.len());
+        ctx.gfx
+            .frame()
+            .encode(ctx, ImageEncodingFormat::Png, format!("{}/{}", self.dir, file))?;
+        self.frames.push(Frame { file, time: self.elapsed });
+        Ok(())
+    }
+
+    /// Encerra a gravação escrevendo o `manifest.json` com o tempo de cada quadro.
+    pub fn finish(self, ctx: &Context) -> GameResult {
+        let file = ctx.fs.create(format!("{}/manifest.json", self.dir))?;
+        let manifest = Manifest {
+            fps: CAPTURE_FPS,
+            frames: &self.frames,
+        };
+        serde_json::to_writer_pretty(file, &manifest).map_err(|e| ggez::GameError::CustomError(e.to_string()))
+    }
+}
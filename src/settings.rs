@@ -0,0 +1,473 @@
+use crate::config::{
+    BodyStyle, Config, ControlScheme, LethalMealPolicy, MAX_ARENA_SCALE, MAX_AUTO_RETURN_DELAY, MAX_CHAIN_WINDOW,
+    MAX_CURSE_DURATION, MAX_DAY_CYCLE_MINUTES, MAX_ENEMIES, MAX_FOOD_CENTER_BIAS, MAX_FRAMES_PER_TICK, MAX_LENGTH_LIMIT,
+    MAX_MAGNET_INTERVAL, MAX_MAZE_DENSITY, MAX_PARTICLES, MAX_PROJECTION_LENGTH, MAX_PULSE_BPM, MAX_SPEED_OFFSET,
+    MAX_START_GRACE, MAX_STARTING_SCORE, MAX_TRAIL_WALL_DELAY, MAX_UI_SCALE, MAX_WALL_LIVES, MAX_ZOOM, MIN_CURSE_DURATION,
+    MIN_MAX_LENGTH, MIN_PARTICLES, MIN_PULSE_BPM, MIN_UI_SCALE, MIN_ZOOM, Preset, RESOLUTIONS, Resolution, ReversePolicy,
+};
+use crate::theme::Theme;
+
+/// Entradas da tela de configurações, na ordem em que são exibidas.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Setting {
+    Trail,
+    StartingScore,
+    StartingSpeedOffset,
+    WallBounce,
+    FoodCenterBias,
+    ShrinkingArena,
+    ReversePolicy,
+    MovingFood,
+    EnemyCount,
+    Volume,
+    Muted,
+    TurnSound,
+    WallSound,
+    FoodEnabled,
+    Controls,
+    ArenaScale,
+    Minimap,
+    SpeedGauge,
+    Zoom,
+    UiScale,
+    SpeedPads,
+    Samples,
+    Resolution,
+    PassableBorder,
+    Theme,
+    WallPulse,
+    PulseBpm,
+    DeathExplosion,
+    MaxParticles,
+    WrapEdges,
+    AutoReturnDelay,
+    GrowthAnimation,
+    Tongue,
+    VisionCone,
+    SmoothTail,
+    ScorePopups,
+    MenuPreview,
+    DayCycle,
+    BodyStyle,
+    TouchDpad,
+    ProjectionLength,
+    DirectionStarts,
+    HighVisibility,
+    WallLives,
+    StartGrace,
+    FramesPerTick,
+    FoodTrail,
+    SpecialFood,
+    FoodCountdown,
+    ReverseWarning,
+    InstantTurns,
+    LethalMeal,
+    CurseChance,
+    CurseDuration,
+    MaxLength,
+    FoodMagnet,
+    MazeDensity,
+    TrailWalls,
+    ChainWindow,
+    /// Aplica o estilo pronto ao ser escolhida, com qualquer tecla.
+    Preset(Preset),
+}
+
+pub const SETTINGS: &[Setting] = &[
+    Setting::Trail,
+    Setting::StartingScore,
+    Setting::StartingSpeedOffset,
+    Setting::FramesPerTick,
+    Setting::WallBounce,
+    Setting::WrapEdges,
+    Setting::WallLives,
+    Setting::StartGrace,
+    Setting::FoodCenterBias,
+    Setting::ShrinkingArena,
+    Setting::ReversePolicy,
+    Setting::ReverseWarning,
+    Setting::InstantTurns,
+    Setting::LethalMeal,
+    Setting::MovingFood,
+    Setting::FoodTrail,
+    Setting::FoodMagnet,
+    Setting::SpecialFood,
+    Setting::FoodCountdown,
+    Setting::ChainWindow,
+    Setting::CurseChance,
+    Setting::CurseDuration,
+    Setting::MazeDensity,
+    Setting::TrailWalls,
+    Setting::EnemyCount,
+    Setting::Volume,
+    Setting::Muted,
+    Setting::TurnSound,
+    Setting::WallSound,
+    Setting::FoodEnabled,
+    Setting::MaxLength,
+    Setting::ProjectionLength,
+    Setting::Controls,
+    Setting::DirectionStarts,
+    Setting::TouchDpad,
+    Setting::ArenaScale,
+    Setting::Minimap,
+    Setting::SpeedGauge,
+    Setting::Zoom,
+    Setting::UiScale,
+    Setting::SpeedPads,
+    Setting::Samples,
+    Setting::Resolution,
+    Setting::PassableBorder,
+    Setting::Preset(Preset::Nokia3310),
+    Setting::Theme,
+    Setting::HighVisibility,
+    Setting::WallPulse,
+    Setting::PulseBpm,
+    Setting::DeathExplosion,
+    Setting::MaxParticles,
+    Setting::GrowthAnimation,
+    Setting::SmoothTail,
+    Setting::Tongue,
+    Setting::VisionCone,
+    Setting::ScorePopups,
+    Setting::MenuPreview,
+    Setting::DayCycle,
+    Setting::BodyStyle,
+    Setting::AutoReturnDelay,
+];
+
+const PARTICLES_STEP: usize = 100;
+const MAX_LENGTH_STEP: usize = 5;
+
+fn on_off(value: bool) -> &'static str {
+    if value { "Ligado" } else { "Desligado" }
+}
+
+impl Setting {
+    /// Texto exibido na lista, já com o valor atual.
+    pub fn label(&self, config: &Config) -> String {
+        match *self {
+            Setting::Trail => format!("Rastro: {}", on_off(config.trail_enabled)),
+            Setting::StartingScore => format!("Pontuação inicial: {}", config.starting_score),
+            Setting::StartingSpeedOffset => {
+                format!("Lentidão inicial: +{:.2}s", config.starting_speed_offset)
+            }
+            Setting::WallBounce => {
+                let mode = if config.wall_bounce { "Seguram a cobra" } else { "Fatais" };
+                format!("Paredes: {}", mode)
+            }
+            Setting::WrapEdges => format!("Atravessar bordas: {}", on_off(config.wrap_edges)),
+            Setting::FoodCenterBias => format!("Comida no centro: {:.1}", config.food_center_bias),
+            Setting::ShrinkingArena => format!("Arena encolhendo: {}", on_off(config.shrinking_arena)),
+            Setting::ReversePolicy => {
+                let policy = match config.reverse_policy {
+                    ReversePolicy::Ignore => "Ignorar",
+                    ReversePolicy::Lethal => "Fatal",
+                };
+                format!("Voltar para trás: {}", policy)
+            }
+            Setting::LethalMeal => {
+                let policy = match config.lethal_meal {
+                    LethalMealPolicy::ScoreThenDie => "Pontua e morre",
+                    LethalMealPolicy::DieWithoutScore => "Morre sem pontuar",
+                };
+                format!("Maçã na parede: {}", policy)
+            }
+            Setting::MovingFood => format!("Comida móvel: {}", on_off(config.moving_food)),
+            Setting::FoodMagnet => match config.food_magnet_interval {
+                0.0 => "Ímã de comida: Desligado".to_string(),
+                secs => format!("Ímã de comida: a cada {:.1}s", secs),
+            },
+            Setting::MaxLength => match config.max_length {
+                Some(len) => format!("Tamanho máximo: {}", len),
+                None => "Tamanho máximo: Sem limite".to_string(),
+            },
+            Setting::FoodTrail => format!("Rastro da comida: {}", on_off(config.food_trail)),
+            Setting::SpecialFood => format!("Maçãs especiais: {}", on_off(config.special_food)),
+            Setting::FoodCountdown => format!("Tempo das maçãs especiais: {}", on_off(config.food_countdown)),
+            Setting::ReverseWarning => format!("Aviso de volta recusada: {}", on_off(config.reverse_warning)),
+            Setting::InstantTurns => format!("Virada imediata: {}", on_off(config.instant_turns)),
+            Setting::CurseChance => match config.curse_chance {
+                0.0 => "Maldição: Desligada".to_string(),
+                chance => format!("Maldição: {:.0}% das especiais", chance * 100.0),
+            },
+            Setting::CurseDuration => format!("Duração da maldição: {:.0}s", config.curse_duration),
+            Setting::MazeDensity => match config.maze_density {
+                0.0 => "Labirinto aleatório: Desligado".to_string(),
+                density => format!("Labirinto aleatório: {:.0}% da arena", density * 100.0),
+            },
+            Setting::TrailWalls => match config.trail_wall_delay {
+                0.0 => "Rastro vira parede: Desligado".to_string(),
+                secs => format!("Rastro vira parede: após {:.0}s", secs),
+            },
+            Setting::ChainWindow => match config.chain_window {
+                0.0 => "Sequência em linha: Desligada".to_string(),
+                secs => format!("Sequência em linha: {:.1}s", secs),
+            },
+            Setting::EnemyCount => format!("Inimigos: {}", config.enemy_count),
+            Setting::Volume => format!("Volume: {:.0}%", config.volume * 100.0),
+            Setting::Muted => format!("Som: {}", if config.muted { "Mudo" } else { "Ligado" }),
+            Setting::TurnSound => format!("Clique ao virar: {}", on_off(config.turn_sound)),
+            Setting::WallSound => format!("Aviso de parede: {}", on_off(config.wall_sound)),
+            Setting::FoodEnabled => format!("Comida: {}", on_off(config.food_enabled)),
+            Setting::Controls => {
+                let scheme = match config.controls {
+                    ControlScheme::Both => "Setas e WASD",
+                    ControlScheme::Arrows => "Só setas",
+                    ControlScheme::Wasd => "Só WASD",
+                };
+                format!("Controles: {}", scheme)
+            }
+            Setting::FramesPerTick => match config.frames_per_tick {
+                0 => "Ritmo: Por tempo".to_string(),
+                frames => format!("Ritmo: 1 passo a cada {} quadros", frames),
+            },
+            Setting::WallLives => match config.wall_lives {
+                0 => "Vidas nas batidas: Morte instantânea".to_string(),
+                lives => format!("Vidas nas batidas: {}", lives),
+            },
+            Setting::StartGrace => match config.start_grace {
+                0.0 => "Paredes seguras no início: Desligado".to_string(),
+                secs => format!("Paredes seguras no início: {:.1}s", secs),
+            },
+            Setting::ProjectionLength => match config.projection_length {
+                0 => "Prever caminho: Desligado".to_string(),
+                cells => format!("Prever caminho: {} células", cells),
+            },
+            Setting::HighVisibility => format!("Alta visibilidade: {}", on_off(config.high_visibility)),
+            Setting::DirectionStarts => format!("Começar com direção: {}", on_off(config.direction_starts)),
+            Setting::TouchDpad => format!("Direcional na tela: {}", on_off(config.touch_dpad)),
+            Setting::ArenaScale => format!("Tamanho da arena: {}x a tela", config.arena_scale),
+            Setting::Minimap => format!("Minimapa: {}", on_off(config.minimap)),
+            Setting::SpeedGauge => format!("Medidor de velocidade: {}", on_off(config.speed_gauge)),
+            Setting::Zoom => format!("Zoom: {:.2}x", config.zoom),
+            Setting::UiScale => match config.ui_scale {
+                0.0 => "Escala da interface: Automática".to_string(),
+                scale => format!("Escala da interface: {:.2}x", scale),
+            },
+            Setting::SpeedPads => format!("Pisos de velocidade: {}", on_off(config.speed_pads)),
+            Setting::Preset(preset) => format!("Aplicar estilo: {}", preset.name()),
+            Setting::Theme => format!("Tema: {}", config.theme),
+            Setting::WallPulse => format!("Paredes pulsando: {}", on_off(config.wall_pulse)),
+            Setting::PulseBpm => format!("Ritmo do pulso: {} bpm", config.pulse_bpm),
+            Setting::DeathExplosion => format!("Explosão ao morrer: {}", on_off(config.death_explosion)),
+            Setting::MaxParticles => format!("Limite de partículas: {}", config.max_particles),
+            Setting::GrowthAnimation => format!("Animação ao crescer: {}", on_off(config.growth_animation)),
+            Setting::Tongue => format!("Língua: {}", on_off(config.tongue)),
+            Setting::VisionCone => format!("Cone de visão: {}", on_off(config.vision_cone)),
+            Setting::SmoothTail => format!("Cauda suave: {}", on_off(config.smooth_tail)),
+            Setting::ScorePopups => format!("Pontos flutuantes: {}", on_off(config.score_popups)),
+            Setting::MenuPreview => format!("Cobra andando no menu: {}", on_off(config.menu_preview)),
+            Setting::DayCycle => match config.day_cycle_minutes {
+                0 => "Ciclo do dia: Desligado".to_string(),
+                minutes => format!("Ciclo do dia: {} min", minutes),
+            },
+            Setting::BodyStyle => {
+                let style = match config.body_style {
+                    BodyStyle::Blocks => "Blocos",
+                    BodyStyle::Path => "Contínuo",
+                };
+                format!("Corpo da cobra: {}", style)
+            }
+            Setting::AutoReturnDelay => match config.auto_return_delay {
+                0 => "Voltar ao menu sozinho: Desligado".to_string(),
+                secs => format!("Voltar ao menu sozinho: após {}s", secs),
+            },
+            Setting::PassableBorder => {
+                let border = if config.passable_border { "Zona de aviso" } else { "Parede" };
+                format!("Borda: {}", border)
+            }
+            Setting::Samples => {
+                let samples = if config.samples > 1 { "4x" } else { "Desligado" };
+                format!("Antisserrilhado: {} (ao reabrir)", samples)
+            }
+            Setting::Resolution => match config.resolution {
+                Resolution::Windowed { width, height } => format!("Resolução: {}x{}", width, height),
+                Resolution::Fullscreen => "Resolução: Tela cheia".to_string(),
+            },
+        }
+    }
+
+    /// Altera o valor da opção; `delta` é -1 ou +1 conforme a tecla usada.
+    /// `themes` são os temas disponíveis, na ordem em que são percorridos.
+    pub fn adjust(&self, config: &mut Config, delta: i32, themes: &[Theme]) {
+        match *self {
+            Setting::Trail => config.trail_enabled = !config.trail_enabled,
+            Setting::WallBounce => config.wall_bounce = !config.wall_bounce,
+            Setting::WrapEdges => config.wrap_edges = !config.wrap_edges,
+            Setting::ShrinkingArena => config.shrinking_arena = !config.shrinking_arena,
+            Setting::MovingFood => config.moving_food = !config.moving_food,
+            Setting::FoodTrail => config.food_trail = !config.food_trail,
+            Setting::SpecialFood => config.special_food = !config.special_food,
+            Setting::FoodCountdown => config.food_countdown = !config.food_countdown,
+            Setting::ReverseWarning => config.reverse_warning = !config.reverse_warning,
+            Setting::InstantTurns => config.instant_turns = !config.instant_turns,
+            Setting::Muted => config.muted = !config.muted,
+            Setting::TurnSound => config.turn_sound = !config.turn_sound,
+            Setting::WallSound => config.wall_sound = !config.wall_sound,
+            Setting::FoodEnabled => config.food_enabled = !config.food_enabled,
+            Setting::Minimap => config.minimap = !config.minimap,
+            Setting::SpeedGauge => config.speed_gauge = !config.speed_gauge,
+            Setting::TouchDpad => config.touch_dpad = !config.touch_dpad,
+            Setting::DirectionStarts => config.direction_starts = !config.direction_starts,
+            Setting::HighVisibility => config.high_visibility = !config.high_visibility,
+            Setting::SpeedPads => config.speed_pads = !config.speed_pads,
+            Setting::PassableBorder => config.passable_border = !config.passable_border,
+            Setting::WallPulse => config.wall_pulse = !config.wall_pulse,
+            Setting::DeathExplosion => config.death_explosion = !config.death_explosion,
+            Setting::GrowthAnimation => config.growth_animation = !config.growth_animation,
+            Setting::Tongue => config.tongue = !config.tongue,
+            Setting::VisionCone => config.vision_cone = !config.vision_cone,
+            Setting::SmoothTail => config.smooth_tail = !config.smooth_tail,
+            Setting::ScorePopups => config.score_popups = !config.score_popups,
+            Setting::MenuPreview => config.menu_preview = !config.menu_preview,
+            Setting::PulseBpm => {
+                config.pulse_bpm = config
+                    .pulse_bpm
+                    .saturating_add_signed(delta * 5)
+                    .clamp(MIN_PULSE_BPM, MAX_PULSE_BPM);
+            }
+            Setting::AutoReturnDelay => {
+                config.auto_return_delay = config
+                    .auto_return_delay
+                    .saturating_add_signed(delta * 5)
+                    .min(MAX_AUTO_RETURN_DELAY);
+            }
+            Setting::Samples => config.samples = if config.samples > 1 { 1 } else { 4 },
+            Setting::Resolution => {
+                // Um tamanho fora da lista, vindo do arquivo, recomeça do primeiro.
+                let next = match RESOLUTIONS.iter().position(|&r| r == config.resolution) {
+                    Some(current) => (current as i32 + delta).rem_euclid(RESOLUTIONS.len() as i32) as usize,
+                    None => 0,
+                };
+                config.resolution = RESOLUTIONS[next];
+            }
+            Setting::Volume => {
+                let volume = config.volume + delta as f32 * 0.1;
+                config.volume = ((volume * 10.0).round() / 10.0).clamp(0.0, 1.0);
+            }
+            Setting::ReversePolicy => {
+                config.reverse_policy = match config.reverse_policy {
+                    ReversePolicy::Ignore => ReversePolicy::Lethal,
+                    ReversePolicy::Lethal => ReversePolicy::Ignore,
+                };
+            }
+            Setting::LethalMeal => {
+                config.lethal_meal = match config.lethal_meal {
+                    LethalMealPolicy::ScoreThenDie => LethalMealPolicy::DieWithoutScore,
+                    LethalMealPolicy::DieWithoutScore => LethalMealPolicy::ScoreThenDie,
+                };
+            }
+            Setting::BodyStyle => {
+                config.body_style = match config.body_style {
+                    BodyStyle::Blocks => BodyStyle::Path,
+                    BodyStyle::Path => BodyStyle::Blocks,
+                };
+            }
+            Setting::Controls => {
+                const SCHEMES: [ControlScheme; 3] = [ControlScheme::Both, ControlScheme::Arrows, ControlScheme::Wasd];
+                let current = SCHEMES.iter().position(|&s| s == config.controls).unwrap_or(0);
+                let next = (current as i32 + delta).rem_euclid(SCHEMES.len() as i32);
+                config.controls = SCHEMES[next as usize];
+            }
+            Setting::Preset(preset) => preset.apply(config),
+            Setting::Theme => {
+                let current = themes.iter().position(|t| t.name == config.theme).unwrap_or(0);
+                let next = (current as i32 + delta).rem_euclid(themes.len() as i32);
+                config.theme = themes[next as usize].name.clone();
+            }
+            Setting::ArenaScale => {
+                config.arena_scale = config.arena_scale.saturating_add_signed(delta).clamp(1, MAX_ARENA_SCALE);
+            }
+            Setting::StartingScore => {
+                config.starting_score = config
+                    .starting_score
+                    .saturating_add_signed(delta)
+                    .min(MAX_STARTING_SCORE);
+            }
+            Setting::StartingSpeedOffset => {
+                let offset = config.starting_speed_offset + delta as f32 * 0.01;
+                // Arredonda para evitar acúmulo de erro ao somar 0.01 várias vezes.
+                config.starting_speed_offset = ((offset * 100.0).round() / 100.0).clamp(0.0, MAX_SPEED_OFFSET);
+            }
+            Setting::CurseChance => {
+                let chance = config.curse_chance + delta as f32 * 0.1;
+                config.curse_chance = ((chance * 10.0).round() / 10.0).clamp(0.0, 1.0);
+            }
+            Setting::CurseDuration => {
+                config.curse_duration = (config.curse_duration + delta as f32).clamp(MIN_CURSE_DURATION, MAX_CURSE_DURATION);
+            }
+            Setting::FoodMagnet => {
+                let interval = config.food_magnet_interval + delta as f32 * 0.5;
+                config.food_magnet_interval = ((interval * 2.0).round() / 2.0).clamp(0.0, MAX_MAGNET_INTERVAL);
+            }
+            Setting::MaxLength => {
+                // Abaixo do menor limite volta a ser ilimitado, e vice-versa.
+                config.max_length = match config.max_length {
+                    None if delta > 0 => Some(MIN_MAX_LENGTH.max(MAX_LENGTH_STEP)),
+                    None => Some(MAX_LENGTH_LIMIT),
+                    Some(len) => {
+                        let len = len as i32 + delta * MAX_LENGTH_STEP as i32;
+                        (MIN_MAX_LENGTH as i32..=MAX_LENGTH_LIMIT as i32)
+                            .contains(&len)
+                            .then_some(len as usize)
+                    }
+                };
+            }
+            Setting::FramesPerTick => {
+                config.frames_per_tick = config.frames_per_tick.saturating_add_signed(delta).min(MAX_FRAMES_PER_TICK);
+            }
+            Setting::StartGrace => {
+                let grace = config.start_grace + delta as f32 * 0.5;
+                config.start_grace = ((grace * 2.0).round() / 2.0).clamp(0.0, MAX_START_GRACE);
+            }
+            Setting::WallLives => {
+                config.wall_lives = config.wall_lives.saturating_add_signed(delta).min(MAX_WALL_LIVES);
+            }
+            Setting::ChainWindow => {
+                let window = config.chain_window + delta as f32 * 0.5;
+                config.chain_window = ((window * 2.0).round() / 2.0).clamp(0.0, MAX_CHAIN_WINDOW);
+            }
+            Setting::TrailWalls => {
+                config.trail_wall_delay = (config.trail_wall_delay + delta as f32).clamp(0.0, MAX_TRAIL_WALL_DELAY);
+            }
+            Setting::MazeDensity => {
+                let density = config.maze_density + delta as f32 * 0.05;
+                config.maze_density = ((density * 20.0).round() / 20.0).clamp(0.0, MAX_MAZE_DENSITY);
+            }
+            Setting::Zoom => {
+                let zoom = config.zoom + delta as f32 * 0.25;
+                config.zoom = ((zoom * 4.0).round() / 4.0).clamp(MIN_ZOOM, MAX_ZOOM);
+            }
+            Setting::UiScale => {
+                // Abaixo da menor escala fica o automático.
+                let current = if config.ui_scale == 0.0 { MIN_UI_SCALE - 0.25 } else { config.ui_scale };
+                let scale = ((current + delta as f32 * 0.25) * 4.0).round() / 4.0;
+                config.ui_scale = if scale < MIN_UI_SCALE { 0.0 } else { scale.min(MAX_UI_SCALE) };
+            }
+            Setting::MaxParticles => {
+                let max = config.max_particles.saturating_add_signed(delta as isize * PARTICLES_STEP as isize);
+                config.max_particles = max.clamp(MIN_PARTICLES, MAX_PARTICLES);
+            }
+            Setting::DayCycle => {
+                config.day_cycle_minutes = config
+                    .day_cycle_minutes
+                    .saturating_add_signed(delta)
+                    .min(MAX_DAY_CYCLE_MINUTES);
+            }
+            Setting::ProjectionLength => {
+                config.projection_length = config
+                    .projection_length
+                    .saturating_add_signed(delta)
+                    .min(MAX_PROJECTION_LENGTH);
+            }
+            Setting::EnemyCount => {
+                config.enemy_count = config.enemy_count.saturating_add_signed(delta).min(MAX_ENEMIES);
+            }
+            Setting::FoodCenterBias => {
+                let bias = config.food_center_bias + delta as f32 * 0.5;
+                config.food_center_bias = bias.clamp(0.0, MAX_FOOD_CENTER_BIAS);
+            }
+        }
+    }
+}
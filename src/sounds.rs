@@ -0,0 +1,45 @@
+use ggez::audio::{SoundSource, Source};
+use ggez::{Context, GameResult};
+
+const TURN_SOUND_PATH: &str = "/sfx_turn.ogg";
+const WALL_SOUND_PATH: &str = "/sfx_wall.ogg";
+
+/// Avisos sonoros curtos da partida.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Cue {
+    /// Um clique baixo quando a cobra vira.
+    Turn,
+    /// Um tom subindo quando a cabeça vai bater na parede no próximo passo.
+    Wall,
+}
+
+/// Efeitos sonoros. Arquivos ausentes simplesmente não tocam.
+#[derive(Default)]
+pub struct Sounds {
+    turn: Option<Source>,
+    wall: Option<Source>,
+}
+
+impl Sounds {
+    pub fn load(ctx: &mut Context) -> Sounds {
+        Sounds {
+            turn: Source::new(ctx, TURN_SOUND_PATH).ok(),
+            wall: Source::new(ctx, WALL_SOUND_PATH).ok(),
+        }
+    }
+
+    /// Toca o aviso por cima do que já estiver tocando, no volume dado.
+    pub fn play(&mut self, ctx: &Context, cue: Cue, volume: f32) -> GameResult {
+        let source = match cue {
+            Cue::Turn => self.turn.as_mut(),
+            Cue::Wall => self.wall.as_mut(),
+        };
+        if let Some(source) = source
+            && volume > 0.0
+        {
+            source.set_volume(volume);
+            source.play_detached(ctx)?;
+        }
+        Ok(())
+    }
+}
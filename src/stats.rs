@@ -0,0 +1,47 @@
+use ggez::{Context, GameResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::DeathCause;
+
+const STATS_PATH: &str = "/stats.json";
+
+/// Totais acumulados de todas as partidas já jogadas.
+///
+/// Campos ausentes no arquivo (por exemplo, gravado por uma versão anterior)
+/// começam do zero, sem descartar os que já existem.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub games_played: u32,
+    /// Maçãs comuns e especiais.
+    pub food_eaten: u32,
+    pub best_score: u32,
+    /// Segundos jogados, somando todas as partidas.
+    pub play_time: f32,
+    pub deaths: BTreeMap<DeathCause, u32>,
+}
+
+impl Stats {
+    pub fn load(ctx: &Context) -> Stats {
+        ctx.fs
+            .open(STATS_PATH)
+            .ok()
+            .and_then(|file| serde_json::from_reader(file).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, ctx: &Context) -> GameResult {
+        let file = ctx.fs.create(STATS_PATH)?;
+        serde_json::to_writer_pretty(file, self)
+            .map_err(|e| ggez::GameError::CustomError(e.to_string()))
+    }
+
+    /// Soma uma partida encerrada aos totais.
+    pub fn record_run(&mut self, score: u32, cause: DeathCause, play_time: f32) {
+        self.games_played += 1;
+        self.best_score = self.best_score.max(score);
+        self.play_time += play_time;
+        *self.deaths.entry(cause).or_insert(0) += 1;
+    }
+}
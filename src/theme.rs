@@ -0,0 +1,255 @@
+use ggez::Context;
+use ggez::graphics::Color;
+use serde::Deserialize;
+
+/// Formato dos olhos desenhados na cabeça da cobra.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EyeStyle {
+    #[default]
+    Square,
+    Dot,
+    /// Fendas finas, alongadas na direção do movimento.
+    Slit,
+    /// Cortados em diagonal, como sobrancelhas franzidas.
+    Angry,
+}
+
+/// Formato da maçã comum. Com cores parecidas, o formato ainda a distingue.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FoodShape {
+    #[default]
+    Square,
+    Circle,
+    Diamond,
+    Star,
+}
+
+/// Como o chão da arena é desenhado.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GridStyle {
+    /// Contorno fino em cada célula (padrão).
+    #[default]
+    Lines,
+    /// Células alternadas entre o fundo e a cor da grade, como num tabuleiro.
+    Checkerboard,
+    /// Só a cor de fundo, sem grade.
+    None,
+}
+
+/// Caracteres que substituem os blocos no modo de letreiro: cada segmento e
+/// a maçã viram um texto na fonte do jogo, centralizado na célula.
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+pub struct Glyphs {
+    pub head: String,
+    pub body: String,
+    pub food: String,
+}
+
+const DEFAULT_SEGMENT_GAP: f32 = 2.0;
+/// Nome do tema da predefinição Nokia 3310.
+pub const NOKIA_THEME: &str = "Nokia 3310";
+
+// Pasta de recursos onde o jogador pode colocar temas próprios.
+const THEMES_DIR: &str = "/themes";
+
+/// Cores usadas para desenhar a arena e as cobras.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub name: String,
+    pub background: Color,
+    pub grid: Color,
+    pub wall: Color,
+    pub snake_head: Color,
+    pub snake_body: Color,
+    pub food: Color,
+    pub enemy_head: Color,
+    pub enemy_body: Color,
+    /// Sombra desenhada atrás dos textos, útil com fundos claros.
+    pub text_shadow: Option<Color>,
+    pub eyes: EyeStyle,
+    /// O corpo escurece aos poucos da cabeça até a ponta da cauda.
+    pub body_gradient: bool,
+    pub food_shape: FoodShape,
+    pub grid_style: GridStyle,
+    /// Margem, em pixels, de cada lado dos blocos da cobra: 0 deixa o corpo
+    /// contínuo e valores maiores o separam em contas.
+    pub segment_gap: f32,
+    /// Com caracteres definidos, a cobra e a maçã são desenhadas como texto.
+    pub glyphs: Option<Glyphs>,
+}
+
+/// Formato de um arquivo de tema: as mesmas cores de `Theme`, como `[r, g, b]`
+/// ou `[r, g, b, a]` com valores de 0.0 a 1.0. `text_shadow`, `eyes`
+/// (`"square"`, `"dot"`, `"slit"` ou `"angry"`), `body_gradient` e
+/// `food_shape` (`"square"`, `"circle"`, `"diamond"` ou `"star"`) e
+/// `grid_style` (`"lines"`, `"checkerboard"` ou `"none"`), `segment_gap`
+/// (pixels, menos que metade de um bloco) e `glyphs` (`{"head", "body",
+/// "food"}`, cada um um texto não vazio) são opcionais. Os glifos saem na
+/// fonte principal, a BungeeShade, que não tem a maioria dos símbolos fora do
+/// ASCII; um caractere que falta nela não aparece.
+#[derive(Deserialize)]
+struct ThemeFile {
+    name: String,
+    background: Vec<f32>,
+    grid: Vec<f32>,
+    wall: Vec<f32>,
+    snake_head: Vec<f32>,
+    snake_body: Vec<f32>,
+    food: Vec<f32>,
+    enemy_head: Vec<f32>,
+    enemy_body: Vec<f32>,
+    #[serde(default)]
+    text_shadow: Option<Vec<f32>>,
+    #[serde(default)]
+    eyes: EyeStyle,
+    #[serde(default)]
+    body_gradient: bool,
+    #[serde(default)]
+    food_shape: FoodShape,
+    #[serde(default)]
+    grid_style: GridStyle,
+    #[serde(default = "default_segment_gap")]
+    segment_gap: f32,
+    #[serde(default)]
+    glyphs: Option<Glyphs>,
+}
+
+fn default_segment_gap() -> f32 {
+    DEFAULT_SEGMENT_GAP
+}
+
+fn parse_color(field: &str, values: &[f32]) -> Result<Color, String> {
+    if values.iter().any(|v| !(0.0..=1.0).contains(v)) {
+        return Err(format!("\"{}\" tem valores fora de 0.0 a 1.0", field));
+    }
+    match *values {
+        [r, g, b] => Ok(Color::new(r, g, b, 1.0)),
+        [r, g, b, a] => Ok(Color::new(r, g, b, a)),
+        _ => Err(format!("\"{}\" precisa de 3 ou 4 valores", field)),
+    }
+}
+
+impl ThemeFile {
+    fn into_theme(self) -> Result<Theme, String> {
+        // A margem dos dois lados precisa deixar algo do bloco para desenhar.
+        if !(0.0..crate::BLOCK_SIZE / 2.0).contains(&self.segment_gap) {
+            return Err(format!("\"segment_gap\" precisa ficar entre 0 e {}", crate::BLOCK_SIZE / 2.0));
+        }
+        if let Some(glyphs) = &self.glyphs
+            && [&glyphs.head, &glyphs.body, &glyphs.food].iter().any(|g| g.trim().is_empty())
+        {
+            return Err("\"glyphs\" não pode ter textos vazios".to_string());
+        }
+        Ok(Theme {
+            background: parse_color("background", &self.background)?,
+            grid: parse_color("grid", &self.grid)?,
+            wall: parse_color("wall", &self.wall)?,
+            snake_head: parse_color("snake_head", &self.snake_head)?,
+            snake_body: parse_color("snake_body", &self.snake_body)?,
+            food: parse_color("food", &self.food)?,
+            enemy_head: parse_color("enemy_head", &self.enemy_head)?,
+            enemy_body: parse_color("enemy_body", &self.enemy_body)?,
+            text_shadow: self.text_shadow.map(|c| parse_color("text_shadow", &c)).transpose()?,
+            eyes: self.eyes,
+            body_gradient: self.body_gradient,
+            food_shape: self.food_shape,
+            grid_style: self.grid_style,
+            segment_gap: self.segment_gap,
+            glyphs: self.glyphs,
+            name: self.name,
+        })
+    }
+}
+
+/// Temas que acompanham o jogo. O primeiro é o padrão.
+pub fn built_in() -> Vec<Theme> {
+    vec![
+        Theme {
+            name: "Clássico".to_string(),
+            background: Color::from([0.15, 0.17, 0.18, 1.0]),
+            grid: Color::from([0.2, 0.22, 0.23, 1.0]),
+            wall: Color::from([0.4, 0.4, 0.4, 1.0]),
+            snake_head: Color::from([0.9, 0.5, 0.2, 1.0]),
+            snake_body: Color::from([0.8, 0.4, 0.1, 1.0]),
+            food: Color::RED,
+            enemy_head: Color::from([0.4, 0.8, 0.3, 1.0]),
+            enemy_body: Color::from([0.25, 0.6, 0.2, 1.0]),
+            text_shadow: None,
+            eyes: EyeStyle::Square,
+            body_gradient: false,
+            food_shape: FoodShape::Square,
+            grid_style: GridStyle::Lines,
+            segment_gap: DEFAULT_SEGMENT_GAP,
+            glyphs: None,
+        },
+        Theme {
+            name: "Noite".to_string(),
+            background: Color::from([0.05, 0.06, 0.12, 1.0]),
+            grid: Color::from([0.1, 0.12, 0.2, 1.0]),
+            wall: Color::from([0.25, 0.28, 0.45, 1.0]),
+            snake_head: Color::from([0.5, 0.85, 1.0, 1.0]),
+            snake_body: Color::from([0.3, 0.6, 0.9, 1.0]),
+            food: Color::from([1.0, 0.4, 0.7, 1.0]),
+            enemy_head: Color::from([0.9, 0.9, 0.4, 1.0]),
+            enemy_body: Color::from([0.7, 0.7, 0.3, 1.0]),
+            text_shadow: None,
+            eyes: EyeStyle::Slit,
+            body_gradient: true,
+            food_shape: FoodShape::Circle,
+            grid_style: GridStyle::Lines,
+            segment_gap: DEFAULT_SEGMENT_GAP,
+            glyphs: None,
+        },
+        // Verde sobre verde, como a tela de cristal líquido do celular. A grade
+        // quase some no fundo, como os pixels apagados da tela.
+        Theme {
+            name: NOKIA_THEME.to_string(),
+            background: Color::from([0.6, 0.7, 0.35, 1.0]),
+            grid: Color::from([0.56, 0.66, 0.32, 1.0]),
+            wall: Color::from([0.17, 0.24, 0.1, 1.0]),
+            snake_head: Color::from([0.17, 0.24, 0.1, 1.0]),
+            snake_body: Color::from([0.17, 0.24, 0.1, 1.0]),
+            food: Color::from([0.17, 0.24, 0.1, 1.0]),
+            enemy_head: Color::from([0.3, 0.38, 0.18, 1.0]),
+            enemy_body: Color::from([0.3, 0.38, 0.18, 1.0]),
+            text_shadow: None,
+            eyes: EyeStyle::Square,
+            body_gradient: false,
+            food_shape: FoodShape::Diamond,
+            grid_style: GridStyle::Lines,
+            segment_gap: DEFAULT_SEGMENT_GAP,
+            glyphs: None,
+        },
+    ]
+}
+
+/// Os temas embutidos seguidos dos arquivos `.json` de `themes/`. Arquivos
+/// inválidos são ignorados com um aviso no terminal.
+pub fn load_all(ctx: &Context) -> Vec<Theme> {
+    let mut themes = built_in();
+    let Ok(entries) = ctx.fs.read_dir(THEMES_DIR) else {
+        return themes;
+    };
+    let mut paths: Vec<_> = entries.filter(|p| p.extension().is_some_and(|e| e == "json")).collect();
+    paths.sort();
+
+    for path in paths {
+        let theme = ctx
+            .fs
+            .open(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|file| serde_json::from_reader::<_, ThemeFile>(file).map_err(|e| e.to_string()))
+            .and_then(ThemeFile::into_theme);
+        match theme {
+            Ok(theme) if themes.iter().any(|t| t.name == theme.name) => {
+                eprintln!("Tema {} ignorado: já existe um tema chamado \"{}\"", path.display(), theme.name);
+            }
+            Ok(theme) => themes.push(theme),
+            Err(e) => eprintln!("Tema {} ignorado: {}", path.display(), e),
+        }
+    }
+    themes
+}